@@ -0,0 +1,119 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::evaluator::{self, EvaluationBuffer};
+use cgp::global_params::CgpParameters;
+use cgp::utils::node_type::NodeType;
+use cgp::utils::random::CgpRng;
+use criterion::measurement::WallTime;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+// about the size of cal_housing and bike_sharing_hour
+const SAMPLE_COUNTS: &[usize] = &[1_000, 20_000];
+const NBR_FEATURES: usize = 8;
+const NBR_CHROMOSOMES: usize = 20;
+// with the default set most of the time goes into sin, tan, exp and so on,
+// the arithmetic set shows the overhead of the evaluator itself
+const FUNCTION_SETS: &[&str] = &["default", "add,sub,mul,div"];
+
+// the evaluator before the index-based buffer: String keys, a new Vec per
+// node and every input column copied out of the row-major data
+fn legacy_outputs(chromosome: &mut Chromosome, inputs: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    chromosome.get_active_nodes_id();
+    let functions = &chromosome.params.function_set;
+    let mut outputs: HashMap<String, Vec<f32>> = HashMap::new();
+    let mut final_outputs: HashMap<String, Vec<f32>> = HashMap::new();
+    for node_id in chromosome.active_nodes.as_ref().unwrap() {
+        let node = &chromosome.nodes_grid[*node_id];
+        match node.node_type {
+            NodeType::InputNode => {
+                let column: Vec<f32> = inputs.iter().map(|x| x[*node_id]).collect();
+                outputs.insert(node_id.to_string(), column);
+            }
+            NodeType::OutputNode => {
+                let out = outputs[&node.connection1.to_string()].clone();
+                final_outputs.insert(node_id.to_string(), out);
+            }
+            NodeType::ComputationalNode => {
+                let con1 = &outputs[&node.connection1.to_string()];
+                let mut con2 = con1;
+                if functions.arity(node.function_id) == 2 {
+                    con2 = &outputs[&node.connection2.to_string()];
+                }
+                let out: Vec<f32> =
+                    con1.iter().zip(con2).map(|(a, b)| functions.apply(node.function_id, *a, *b)).collect();
+                outputs.insert(node_id.to_string(), out);
+            }
+        }
+    }
+    let mut outs = vec![vec![0.; chromosome.output_node_ids.len()]; inputs.len()];
+    for (k, output_id) in chromosome.output_node_ids.iter().enumerate() {
+        for (out, value) in outs.iter_mut().zip(&final_outputs[&output_id.to_string()]) {
+            out[k] = *value;
+        }
+    }
+    outs
+}
+
+fn setup(nbr_samples: usize, function_set: &str) -> (Vec<Vec<f32>>, Vec<Vec<f32>>, Vec<Chromosome>) {
+    let mut rng = CgpRng::seed_from_u64(0);
+    let rows: Vec<Vec<f32>> = (0..nbr_samples)
+        .map(|_| (0..NBR_FEATURES).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    let labels: Vec<Vec<f32>> = rows.iter().map(|x| vec![x[0] * x[1] + x[2]]).collect();
+    let params = CgpParameters {
+        nbr_inputs: NBR_FEATURES,
+        graph_width: 50,
+        nbr_outputs: 1,
+        function_set: function_set.parse().unwrap(),
+        ..Default::default()
+    };
+    let chromosomes = (0..NBR_CHROMOSOMES).map(|_| Chromosome::new(params.clone(), &mut rng)).collect();
+    (rows, labels, chromosomes)
+}
+
+fn bench_evaluation(c: &mut Criterion) {
+    for function_set in FUNCTION_SETS {
+        let mut group = c.benchmark_group(format!("evaluation/{}", function_set));
+        group.sample_size(10);
+        for &nbr_samples in SAMPLE_COUNTS {
+            bench_samples(&mut group, nbr_samples, function_set);
+        }
+        group.finish();
+    }
+}
+
+fn bench_samples(group: &mut BenchmarkGroup<WallTime>, nbr_samples: usize, function_set: &str) {
+    let (rows, labels, mut chromosomes) = setup(nbr_samples, function_set);
+    let columns = evaluator::to_columns(&rows);
+    let mut buffer = EvaluationBuffer::new();
+
+    // both evaluators have to give bitwise identical outputs
+    for chromosome in chromosomes.iter_mut() {
+        let legacy = legacy_outputs(chromosome, &rows);
+        let buffered = chromosome.predict(&columns, &mut buffer);
+        let bits = |x: &Vec<Vec<f32>>| x.iter().flatten().map(|x| x.to_bits()).collect::<Vec<u32>>();
+        assert_eq!(bits(&legacy), bits(&buffered));
+    }
+
+    // what the runner does for every offspring; the legacy side only computes
+    // the mae while evaluate also fills the case and output errors
+    group.bench_with_input(BenchmarkId::new("string_hashmap", nbr_samples), &rows, |b, rows| {
+        b.iter(|| {
+            for chromosome in chromosomes.iter_mut() {
+                let outs = legacy_outputs(chromosome, rows);
+                black_box(chromosome.calc_mae(&outs, &labels));
+            }
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("index_buffer", nbr_samples), &columns, |b, columns| {
+        b.iter(|| {
+            for chromosome in chromosomes.iter_mut() {
+                black_box(chromosome.evaluate(columns, &labels, &mut buffer));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_evaluation);
+criterion_main!(benches);
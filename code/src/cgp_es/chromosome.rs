@@ -1,19 +1,43 @@
+use crate::datasets::dataset::TaskKind;
 use crate::global_params::CgpParameters as g_params;
+use crate::utils::fitness_metrics::{self, ClassificationObjective, FitnessMetric};
 use crate::utils::node_type::NodeType;
+use crate::utils::pareto::ComplexityMeasure;
+use crate::utils::random::CgpRng;
+use crate::cgp_es::dual::Dual;
+use crate::cgp_es::evaluator::EvaluationBuffer;
 use crate::cgp_es::node::Node;
+use ndarray::Array2;
 use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std;
 use std::collections::HashMap;
 
-#[derive(Clone)]
+// the evaluation results are not serialized, they belong to the data the
+// chromosome was last evaluated on; see cgp_es::model
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Chromosome {
     pub params: g_params,
     pub nodes_grid: Vec<Node>,
     pub output_node_ids: Vec<usize>,
+    #[serde(skip)]
     pub active_nodes: Option<Vec<usize>>,
+    #[serde(skip)]
     pub mae:f32,
+    // regression metric of every output, the fitness is their mean
+    #[serde(skip)]
+    pub output_errors: Vec<f32>,
+    // error on every sample of the last evaluation, used by lexicase selection
+    #[serde(skip)]
+    pub case_errors: Vec<f32>,
+    // linear scaling per output, labels ~ intercept + slope * output
+    pub slopes: Vec<f32>,
+    pub intercepts: Vec<f32>,
+    // values of the constant input nodes, which follow the data inputs
+    pub constants: Vec<f32>,
 }
 
 impl Display for Chromosome {
@@ -21,15 +45,22 @@ impl Display for Chromosome {
         writeln!(f, "+++++++++++++++++ Chromosome +++++++++++")?;
         writeln!(f, "Nodes:")?;
         for node in &self.nodes_grid {
-            write!(f, "{}", *node)?;
+            write!(f, "{}", node.display(&self.params.function_set))?;
         }
         writeln!(f, "Active_nodes: {:?}", self.active_nodes)?;
-        writeln!(f, "Output_nodes: {:?}", self.output_node_ids)
+        writeln!(f, "Output_nodes: {:?}", self.output_node_ids)?;
+        if !self.constants.is_empty() {
+            writeln!(f, "Constants: {:?}", self.constants)?;
+        }
+        for k in 0..self.output_node_ids.len() {
+            writeln!(f, "y{} = {}", k, self.expression(k))?;
+        }
+        Ok(())
     }
 }
 
 impl Chromosome {
-    pub fn new(params: g_params) -> Self {
+    pub fn new(params: g_params, rng: &mut CgpRng) -> Self {
         let mut nodes_grid: Vec<Node> = vec![];
         let mut output_node_ids: Vec<usize> = vec![];
         nodes_grid.reserve(params.nbr_inputs + params.graph_width + params.nbr_outputs);
@@ -42,7 +73,11 @@ impl Chromosome {
                 position,
                 params.nbr_inputs,
                 params.graph_width,
+                params.rows,
+                params.levels_back,
+                params.function_set.len(),
                 NodeType::InputNode,
+                rng,
             ));
         }
         // computational nodes
@@ -51,7 +86,11 @@ impl Chromosome {
                 position,
                 params.nbr_inputs,
                 params.graph_width,
+                params.rows,
+                params.levels_back,
+                params.function_set.len(),
                 NodeType::ComputationalNode,
+                rng,
             ));
         }
         // output nodes
@@ -62,7 +101,11 @@ impl Chromosome {
                 position,
                 params.nbr_inputs,
                 params.graph_width,
+                params.rows,
+                params.levels_back,
+                params.function_set.len(),
                 NodeType::OutputNode,
+                rng,
             ));
         }
 
@@ -72,93 +115,379 @@ impl Chromosome {
             output_node_ids.push(position);
         }
 
+        let nbr_outputs = params.nbr_outputs;
+        let constants = (0..params.nbr_constants)
+            .map(|_| rng.gen_range(-params.constant_range..=params.constant_range))
+            .collect();
         Self {
             params,
             nodes_grid,
             output_node_ids,
             active_nodes: None,
             mae,
+            output_errors: vec![0.; nbr_outputs],
+            case_errors: Vec::new(),
+            slopes: vec![1.; nbr_outputs],
+            intercepts: vec![0.; nbr_outputs],
+            constants,
+        }
+    }
+
+    // training evaluation, refits the linear scaling if it is enabled
+    pub fn evaluate(&mut self, inputs: &Array2<f32>, labels: &Vec<Vec<f32>>, buffer: &mut EvaluationBuffer) -> f32 {
+        self.compute_outputs(inputs, buffer, None);
+        self.fit_outputs(labels, buffer)
+    }
+
+    // evaluate for a mutated clone of parent: only the active nodes that changed
+    // or read from a changed node are recomputed, the rows of the others are
+    // copied from parent_buffer, which has to hold the parent's evaluation on the
+    // same inputs. None if the active graph is the parent's, the fitness is then
+    // the parent's as well and buffer is left untouched, parent_buffer holds the
+    // outputs of both
+    pub fn evaluate_offspring(
+        &mut self,
+        parent: &Chromosome,
+        parent_buffer: &EvaluationBuffer,
+        inputs: &Array2<f32>,
+        labels: &Vec<Vec<f32>>,
+        buffer: &mut EvaluationBuffer,
+    ) -> Option<f32> {
+        self.get_active_nodes_id();
+        let reusable = self.reusable_nodes(parent);
+        if self.active_nodes.as_ref().unwrap().iter().all(|x| reusable[*x]) {
+            return None;
+        }
+        self.compute_outputs(inputs, buffer, Some((parent_buffer, &reusable)));
+        Some(self.fit_outputs(labels, buffer))
+    }
+
+    // per position: active here and in parent with the same genes, reading only
+    // from reusable nodes, so that the parent's row is still valid
+    fn reusable_nodes(&self, parent: &Chromosome) -> Vec<bool> {
+        let mut parent_active = vec![false; self.nodes_grid.len()];
+        for node_id in parent.active_nodes.as_ref().unwrap() {
+            parent_active[*node_id] = true;
+        }
+        let nbr_data_inputs = self.nbr_data_inputs();
+        let mut reusable = vec![false; self.nodes_grid.len()];
+        for node_id in self.active_nodes.as_ref().unwrap() {
+            let node = &self.nodes_grid[*node_id];
+            let previous = &parent.nodes_grid[*node_id];
+            let same_connection1 = || node.connection1 == previous.connection1 && reusable[node.connection1];
+            reusable[*node_id] = parent_active[*node_id]
+                && match node.node_type {
+                    NodeType::InputNode if *node_id >= nbr_data_inputs => {
+                        let index = *node_id - nbr_data_inputs;
+                        self.constants[index].to_bits() == parent.constants[index].to_bits()
+                    }
+                    NodeType::InputNode => true,
+                    NodeType::OutputNode => same_connection1(),
+                    NodeType::ComputationalNode => {
+                        node.function_id == previous.function_id
+                            && same_connection1()
+                            && (self.params.function_set.arity(node.function_id) == 1
+                                || node.connection2 == previous.connection2 && reusable[node.connection2])
+                    }
+                };
+        }
+        reusable
+    }
+
+    // refits the linear scaling if it is enabled and scores the outputs in the buffer
+    fn fit_outputs(&mut self, labels: &Vec<Vec<f32>>, buffer: &mut EvaluationBuffer) -> f32 {
+        if self.uses_linear_scaling() {
+            for k in 0..self.params.nbr_outputs {
+                let predictions: Vec<f32> = buffer.outputs().iter().map(|x| x[k]).collect();
+                let targets: Vec<f32> = labels.iter().map(|x| x[k]).collect();
+                (self.slopes[k], self.intercepts[k]) = fit_linear_scaling(&predictions, &targets);
+            }
+            self.apply_linear_scaling(buffer.outputs_mut());
+        }
+        self.fitness(buffer.outputs(), labels)
+    }
+
+    // evaluation on held-out data with the stored linear scaling
+    pub fn score(&mut self, inputs: &Array2<f32>, labels: &Vec<Vec<f32>>, buffer: &mut EvaluationBuffer) -> f32 {
+        self.compute_outputs(inputs, buffer, None);
+        if self.uses_linear_scaling() {
+            self.apply_linear_scaling(buffer.outputs_mut());
+        }
+        self.fitness(buffer.outputs(), labels)
+    }
+
+    fn fitness(&mut self, outs: &[Vec<f32>], labels: &Vec<Vec<f32>>) -> f32 {
+        self.case_errors = self.calc_case_errors(outs, labels);
+        match self.params.task {
+            TaskKind::Regression => {
+                for k in 0..self.params.nbr_outputs {
+                    let predictions: Vec<f32> = outs.iter().map(|x| x[k]).collect();
+                    let targets: Vec<f32> = labels.iter().map(|x| x[k]).collect();
+                    let mut loss = self.params.metric.evaluate(&predictions, &targets);
+                    if loss.is_nan() {
+                        loss = f32::INFINITY;
+                    }
+                    self.output_errors[k] = loss;
+                }
+                self.mae = self.calc_mae(outs, labels);
+                self.output_errors.iter().sum::<f32>() / self.params.nbr_outputs as f32
+            }
+            TaskKind::Classification => {
+                let fitness = match self.params.classification_objective {
+                    ClassificationObjective::Mcc => self.mcc(outs, labels),
+                    ClassificationObjective::CrossEntropy => fitness_metrics::cross_entropy(outs, labels),
+                    ClassificationObjective::Brier => fitness_metrics::brier_score(outs, labels),
+                };
+                if fitness.is_nan() {
+                    return f32::INFINITY;
+                }
+                fitness
+            }
+        }
+    }
+
+    // regression: mean absolute error over the outputs; classification: 0/1
+    // misclassification for mcc, otherwise the sample's share of the objective
+    fn calc_case_errors(&self, outs: &[Vec<f32>], labels: &[Vec<f32>]) -> Vec<f32> {
+        outs.iter()
+            .zip(labels)
+            .map(|(output, label)| {
+                let error = match (self.params.task, self.params.classification_objective) {
+                    (TaskKind::Regression, _) => {
+                        output.iter().zip(label).map(|(x, y)| (x - y).abs()).sum::<f32>() / output.len() as f32
+                    }
+                    (TaskKind::Classification, ClassificationObjective::Mcc) => {
+                        if get_argmax(output) == get_argmax(label) { 0. } else { 1. }
+                    }
+                    (TaskKind::Classification, ClassificationObjective::CrossEntropy) => {
+                        fitness_metrics::cross_entropy_case(output, label)
+                    }
+                    (TaskKind::Classification, ClassificationObjective::Brier) => {
+                        fitness_metrics::brier_case(output, label)
+                    }
+                };
+                if error.is_nan() { f32::INFINITY } else { error }
+            })
+            .collect()
+    }
+
+    // one row of nbr_outputs values per input row, linearly scaled if enabled
+    pub fn predict(&mut self, inputs: &Array2<f32>, buffer: &mut EvaluationBuffer) -> Vec<Vec<f32>> {
+        self.compute_outputs(inputs, buffer, None);
+        if self.uses_linear_scaling() {
+            self.apply_linear_scaling(buffer.outputs_mut());
+        }
+        buffer.outputs().to_vec()
+    }
+
+    fn uses_linear_scaling(&self) -> bool {
+        self.params.linear_scaling && self.params.task == TaskKind::Regression
+    }
+
+    fn apply_linear_scaling(&self, outs: &mut [Vec<f32>]) {
+        for row in outs.iter_mut() {
+            for (k, value) in row.iter_mut().enumerate() {
+                *value = self.intercepts[k] + self.slopes[k] * *value;
+            }
         }
     }
 
-    pub fn evaluate(&mut self, inputs: &Vec<Vec<f32>>, labels: &Vec<Vec<f32>>,) -> f32 {
+    // the active nodes in position order, each one writes its row of the buffer;
+    // the outputs end up in buffer.outputs(). The rows marked in reuse are copied
+    // from the given buffer instead
+    fn compute_outputs(
+        &mut self,
+        inputs: &Array2<f32>,
+        buffer: &mut EvaluationBuffer,
+        reuse: Option<(&EvaluationBuffer, &[bool])>,
+    ) {
         self.get_active_nodes_id();
-        let mut outputs = HashMap::new();
-        let mut final_outputs = HashMap::new();
+        let nbr_data_inputs = self.nbr_data_inputs();
+        let nbr_samples = inputs.nrows();
+        buffer.prepare(self.nodes_grid.len(), nbr_samples, self.params.nbr_outputs);
 
-        for node_id in self.active_nodes.as_ref().unwrap(){
+        for node_id in self.active_nodes.as_ref().unwrap() {
             let current_node: &Node = &self.nodes_grid[*node_id];
+            let (previous, out) = buffer.split_at_node(*node_id);
+            let row = |position: usize| &previous[position * nbr_samples..(position + 1) * nbr_samples];
+            if let Some((reuse_buffer, reusable)) = reuse {
+                if reusable[*node_id] {
+                    out.copy_from_slice(reuse_buffer.node(*node_id));
+                    continue;
+                }
+            }
 
             match current_node.node_type {
-                NodeType::InputNode => {
-                    // get the input from index node_id
-                    let column: Vec<f32> = inputs
-                        .iter()  // iterate over rows
-                        .map(|x| x[*node_id]) // get the node_id-th element from each row
-                        .collect();
-                    outputs.insert((*node_id).to_string(), column,);
+                NodeType::InputNode if *node_id >= nbr_data_inputs => {
+                    out.fill(self.constants[*node_id - nbr_data_inputs]);
                 }
-                NodeType::OutputNode => {
-                    let con1 = current_node.connection1.to_string();
-                    let out = &outputs[&con1];
-                    let mut slice = vec![0.; out.len()];
-                    for i in 0..out.len(){
-                        slice[i] = out[i];
+                NodeType::InputNode => match inputs.column(*node_id).as_slice() {
+                    Some(column) => out.copy_from_slice(column),
+                    None => {
+                        for (value, input) in out.iter_mut().zip(inputs.column(*node_id)) {
+                            *value = *input;
+                        }
                     }
-                    final_outputs.insert((*node_id).to_string(), slice,);
-                }
+                },
+                NodeType::OutputNode => out.copy_from_slice(row(current_node.connection1)),
                 NodeType::ComputationalNode => {
-                    let con1 = current_node.connection1.to_string();
-                    let con2 = current_node.connection2.to_string();
-                    let con1_slice = &outputs[&con1];
+                    let con1_slice = row(current_node.connection1);
                     let mut con2_slice = con1_slice;
-                    if current_node.function_id < 4{
-                        con2_slice = &outputs[&con2];
+                    if self.params.function_set.arity(current_node.function_id) == 2 {
+                        con2_slice = row(current_node.connection2);
                     }
-                    let out = current_node.execute(con1_slice, con2_slice);
-                    outputs.insert((*node_id).to_string(), out,);
+                    current_node.execute(&self.params.function_set, con1_slice, con2_slice, out);
                 }
             }
         }
-        let output_start_id = (self.params.nbr_inputs + self.params.graph_width) as usize;
-        let output_end_id =
-            (self.params.nbr_inputs + self.params.graph_width + self.params.nbr_outputs) as usize;
-        let mut outs:Vec<Vec<f32>> = vec![vec![0.; self.params.nbr_outputs];  inputs.len()];
 
-        for i in output_start_id..output_end_id{
-            let output_col = &final_outputs[&(i.to_string())];
-            for j in 0..output_col.len(){
-                outs[j][i-output_start_id] = output_col[j];
+        for (k, output_id) in self.output_node_ids.iter().enumerate() {
+            buffer.gather_output(k, *output_id);
+        }
+    }
+
+    // outputs with their derivatives with respect to the constant at index variable,
+    // in the layout of compute_outputs; they end up in buffer.outputs()
+    fn compute_outputs_dual(&mut self, inputs: &Array2<f32>, variable: usize, buffer: &mut EvaluationBuffer<Dual>) {
+        self.get_active_nodes_id();
+        let nbr_data_inputs = self.nbr_data_inputs();
+        let nbr_samples = inputs.nrows();
+        buffer.prepare(self.nodes_grid.len(), nbr_samples, self.params.nbr_outputs);
+
+        for node_id in self.active_nodes.as_ref().unwrap() {
+            let node = &self.nodes_grid[*node_id];
+            let (previous, out) = buffer.split_at_node(*node_id);
+            let row = |position: usize| &previous[position * nbr_samples..(position + 1) * nbr_samples];
+            match node.node_type {
+                NodeType::InputNode if *node_id >= nbr_data_inputs => {
+                    let index = *node_id - nbr_data_inputs;
+                    out.fill(if index == variable {
+                        Dual::variable(self.constants[index])
+                    } else {
+                        Dual::constant(self.constants[index])
+                    });
+                }
+                NodeType::InputNode => {
+                    for (value, input) in out.iter_mut().zip(inputs.column(*node_id)) {
+                        *value = Dual::constant(*input);
+                    }
+                }
+                NodeType::OutputNode => out.copy_from_slice(row(node.connection1)),
+                NodeType::ComputationalNode => {
+                    let con1 = row(node.connection1);
+                    let con2 = if self.params.function_set.arity(node.function_id) == 2 {
+                        row(node.connection2)
+                    } else {
+                        con1
+                    };
+                    node.execute_dual(&self.params.function_set, con1, con2, out);
+                }
             }
         }
-        let mut fitness = 0.;
-        if labels[0].len() == 1{
-            fitness += self.mse(&outs, &labels);
-            self.mae = self.calc_mae(&outs, &labels);
-        } else {
-            fitness +=  self.mcc(&outs, &labels);
+
+        for (k, output_id) in self.output_node_ids.iter().enumerate() {
+            buffer.gather_output(k, *output_id);
         }
-        return fitness;
     }
 
-    pub fn mse(&mut self, outputs: &Vec<Vec<f32>>, labels: &Vec<Vec<f32>>) -> f32{
-        let mut mse = 0.;
-        let j = outputs.len() as f32;
-        for i in 0..outputs.len(){
-            mse += (outputs[i][0] - labels[i][0]) * (outputs[i][0] - labels[i][0]);
+    // levenberg-marquardt on the squared error of the outputs, with the linear
+    // scaling of the last evaluation held fixed. The tuned constants are kept
+    // only if they improve the fitness; returns the fitness and the number of
+    // passes over the data. Only regression tasks are tuned, the squared error
+    // is not what a classification objective minimizes
+    pub fn tune_constants(
+        &mut self,
+        inputs: &Array2<f32>,
+        labels: &Vec<Vec<f32>>,
+        buffer: &mut EvaluationBuffer,
+        fitness: f32,
+        iterations: usize,
+    ) -> (f32, usize) {
+        self.get_active_nodes_id();
+        let nbr_data_inputs = self.nbr_data_inputs();
+        let uses_constants = self.active_nodes
+            .as_ref()
+            .unwrap()
+            .iter()
+            .any(|x| (nbr_data_inputs..self.params.nbr_inputs).contains(x));
+        if self.params.task != TaskKind::Regression || !uses_constants {
+            return (fitness, 0);
         }
-        mse /= j;
-        if mse.is_nan(){
-            mse = f32::INFINITY;
+        let backup = self.clone();
+        let mut dual_buffer = EvaluationBuffer::<Dual>::default();
+        let nbr_constants = self.constants.len();
+        let nbr_outputs = self.params.nbr_outputs;
+        let mut evals = 1;
+        let mut error = squared_error(&self.predict(inputs, buffer), labels);
+        let mut damping = 0.001;
+        for _ in 0..iterations {
+            let mut residuals = vec![0.; inputs.nrows() * nbr_outputs];
+            // one column per constant
+            let mut jacobian = vec![vec![0.; inputs.nrows() * nbr_outputs]; nbr_constants];
+            for (j, column) in jacobian.iter_mut().enumerate() {
+                self.compute_outputs_dual(inputs, j, &mut dual_buffer);
+                evals += 1;
+                for (i, row) in dual_buffer.outputs().iter().enumerate() {
+                    for (k, output) in row.iter().enumerate() {
+                        let (slope, intercept) = if self.uses_linear_scaling() {
+                            (self.slopes[k], self.intercepts[k])
+                        } else {
+                            (1., 0.)
+                        };
+                        residuals[i * nbr_outputs + k] = (intercept + slope * output.value - labels[i][k]) as f64;
+                        column[i * nbr_outputs + k] = (slope * output.derivative) as f64;
+                    }
+                }
+            }
+            if residuals.iter().chain(jacobian.iter().flatten()).any(|x| !x.is_finite()) {
+                break;
+            }
+            // (J^T J + damping * diag(J^T J)) step = -J^T r
+            let dot = |x: &[f64], y: &[f64]| x.iter().zip(y).map(|(a, b)| a * b).sum::<f64>();
+            let mut normal: Vec<Vec<f64>> = jacobian
+                .iter()
+                .map(|x| jacobian.iter().map(|y| dot(x, y)).collect())
+                .collect();
+            let gradient: Vec<f64> = jacobian.iter().map(|x| -dot(x, &residuals)).collect();
+            for (j, row) in normal.iter_mut().enumerate() {
+                row[j] += damping * row[j] + 1e-12;
+            }
+            let step = match solve_linear_system(normal, gradient) {
+                Some(step) => step,
+                None => break,
+            };
+            let previous = self.constants.clone();
+            for (constant, delta) in self.constants.iter_mut().zip(&step) {
+                *constant += *delta as f32;
+            }
+            let candidate_error = squared_error(&self.predict(inputs, buffer), labels);
+            evals += 1;
+            if candidate_error < error {
+                error = candidate_error;
+                damping /= 10.;
+            } else {
+                self.constants = previous;
+                damping *= 10.;
+            }
+        }
+
+        let tuned_fitness = self.evaluate(inputs, labels, buffer);
+        evals += 1;
+        if tuned_fitness.is_nan() || tuned_fitness > fitness {
+            *self = backup;
+            return (fitness, evals);
         }
-        return mse;
+        (tuned_fitness, evals)
     }
 
-    pub fn calc_mae(&mut self, outputs: &Vec<Vec<f32>>, labels: &Vec<Vec<f32>>) -> f32{
+    pub fn calc_mae(&mut self, outputs: &[Vec<f32>], labels: &Vec<Vec<f32>>) -> f32{
+        // averaged over all outputs
         let mut mae = 0.;
-        let j = outputs.len() as f32;
+        let j = (outputs.len() * outputs[0].len()) as f32;
         for i in 0..outputs.len(){
-            mae += (outputs[i][0] - labels[i][0]).abs();
+            for k in 0..outputs[i].len(){
+                mae += (outputs[i][k] - labels[i][k]).abs();
+            }
         }
         mae /= j;
         if mae.is_nan(){
@@ -167,7 +496,7 @@ impl Chromosome {
         return mae;
     }
 
-    pub fn mcc(&mut self, outputs: &Vec<Vec<f32>>, labels: &Vec<Vec<f32>>) -> f32 {
+    pub fn mcc(&mut self, outputs: &[Vec<f32>], labels: &Vec<Vec<f32>>) -> f32 {
         let mut c:f32 = 0.;
         let mut tk:Vec<f32> = vec![0.; outputs[0].len()];
         let mut pk:Vec<f32> = vec![0.; outputs[0].len()];
@@ -187,7 +516,7 @@ impl Chromosome {
         for i in 0..outputs[0].len(){
             mcc -= tk[i] * pk[i];
             preds_square += pk[i].powi(2);
-            gts_square += pk[i].powi(2);
+            gts_square += tk[i].powi(2);
         }
         let divisor = (num_samples.powi(2) - preds_square).sqrt() * (num_samples.powi(2) - gts_square).sqrt();
         if divisor != 0.{
@@ -199,6 +528,87 @@ impl Chromosome {
         return mcc
     }
 
+    // output k written out with the pretty-print forms of the function set;
+    // inputs are called x<index>, constants are written as their value
+    pub fn expression(&self, output: usize) -> String {
+        let mut forms: HashMap<usize, String> = HashMap::new();
+        let mut to_visit = vec![self.nodes_grid[self.output_node_ids[output]].connection1];
+        while let Some(&node_id) = to_visit.last() {
+            let node = &self.nodes_grid[node_id];
+            if node.node_type == NodeType::InputNode && node_id >= self.nbr_data_inputs() {
+                forms.insert(node_id, format!("{}", self.constants[node_id - self.nbr_data_inputs()]));
+                to_visit.pop();
+                continue;
+            }
+            if node.node_type == NodeType::InputNode {
+                forms.insert(node_id, format!("x{}", node_id));
+                to_visit.pop();
+                continue;
+            }
+            let binary = self.params.function_set.arity(node.function_id) == 2;
+            let missing: Vec<usize> = [node.connection1, node.connection2]
+                .iter()
+                .take(if binary { 2 } else { 1 })
+                .cloned()
+                .filter(|x| !forms.contains_key(x))
+                .collect();
+            if !missing.is_empty() {
+                to_visit.extend(missing);
+                continue;
+            }
+            let second = if binary { forms[&node.connection2].as_str() } else { "" };
+            let form = self.params.function_set.format(node.function_id, &forms[&node.connection1], second);
+            forms.insert(node_id, form);
+            to_visit.pop();
+        }
+        let form = forms.remove(&self.nodes_grid[self.output_node_ids[output]].connection1).unwrap();
+        if self.params.linear_scaling && self.params.task == TaskKind::Regression {
+            return format!("{} + {} * {}", self.intercepts[output], self.slopes[output], form);
+        }
+        form
+    }
+
+    // used as second objective by the multi-objective selection
+    pub fn complexity(&mut self, measure: ComplexityMeasure) -> f32 {
+        self.get_active_nodes_id();
+        match measure {
+            ComplexityMeasure::ActiveNodes => self.active_nodes
+                .as_ref()
+                .unwrap()
+                .iter()
+                .filter(|x| self.nodes_grid[**x].node_type == NodeType::ComputationalNode)
+                .count() as f32,
+            ComplexityMeasure::ExpressionSize => self.expression_size() as f32,
+        }
+    }
+
+    // number of nodes of the expression trees of all outputs, inputs included;
+    // active_nodes is sorted, so connections are always visited first
+    fn expression_size(&self) -> u64 {
+        let mut sizes: HashMap<usize, u64> = HashMap::new();
+        let mut total: u64 = 0;
+        for node_id in self.active_nodes.as_ref().unwrap() {
+            let node = &self.nodes_grid[*node_id];
+            let size = match node.node_type {
+                NodeType::InputNode => 1,
+                NodeType::ComputationalNode => {
+                    let mut size = sizes[&node.connection1].saturating_add(1);
+                    if self.params.function_set.arity(node.function_id) == 2 {
+                        size = size.saturating_add(sizes[&node.connection2]);
+                    }
+                    size
+                }
+                NodeType::OutputNode => {
+                    let size = sizes[&node.connection1];
+                    total = total.saturating_add(size);
+                    size
+                }
+            };
+            sizes.insert(*node_id, size);
+        }
+        total
+    }
+
     pub fn get_active_nodes_id(&mut self) {
         let mut active: HashSet<usize, nohash_hasher::BuildNoHashHasher<usize>> =
             HashSet::default();
@@ -224,7 +634,7 @@ impl Chromosome {
                         to_visit.push(connection0);
                         active.insert(connection0);
                     }
-                    if current_node.function_id < 4{
+                    if self.params.function_set.arity(current_node.function_id) == 2{
                         let connection1 = current_node.connection2;
                         if !active.contains(&connection1) {
                             to_visit.push(connection1);
@@ -246,12 +656,17 @@ impl Chromosome {
         self.active_nodes = Some(active);
     }
 
-    pub fn mutate_single(&mut self) {
-        let start_id = self.params.nbr_inputs;
+    // the constants take part as genes, only an active one ends the search
+    pub fn mutate_single(&mut self, rng: &mut CgpRng) {
+        let start_id = self.nbr_data_inputs();
         let end_id = self.params.nbr_inputs + self.params.graph_width + self.params.nbr_outputs;
         loop {
-            let random_node_id = rand::thread_rng().gen_range(start_id..=end_id - 1) as usize;
-            self.nodes_grid[random_node_id].mutate();
+            let random_node_id = rng.gen_range(start_id..end_id);
+            if random_node_id < self.params.nbr_inputs {
+                self.mutate_constant(random_node_id - start_id, rng);
+            } else {
+                self.nodes_grid[random_node_id].mutate(rng);
+            }
 
             if self
                 .active_nodes
@@ -264,21 +679,89 @@ impl Chromosome {
         }
     }
 
-    pub fn mutate_prob(&mut self, prob: f32) {
-        let start_id = self.params.nbr_inputs;
+    pub fn mutate_prob(&mut self, prob: f32, rng: &mut CgpRng) {
+        let start_id = self.nbr_data_inputs();
         let end_id = self.params.nbr_inputs + self.params.graph_width + self.params.nbr_outputs;
         for node_id in start_id..end_id {
-            let random_prob: f32 = rand::thread_rng().gen::<f32>();
+            let random_prob: f32 = rng.gen::<f32>();
             if random_prob < prob {
-                self.nodes_grid[node_id].mutate();
+                if node_id < self.params.nbr_inputs {
+                    self.mutate_constant(node_id - start_id, rng);
+                } else {
+                    self.nodes_grid[node_id].mutate(rng);
+                }
             };
         }
     }
 
+    // gaussian step on every constant
+    pub fn mutate_constants(&mut self, rng: &mut CgpRng) {
+        for i in 0..self.constants.len() {
+            self.mutate_constant(i, rng);
+        }
+    }
+
+    fn mutate_constant(&mut self, index: usize, rng: &mut CgpRng) {
+        let step = Normal::new(0., self.params.constant_sigma).unwrap();
+        self.constants[index] += step.sample(rng);
+    }
+
+    fn nbr_data_inputs(&self) -> usize {
+        self.params.nbr_inputs - self.params.nbr_constants
+    }
+
     pub fn get_mae(&mut self) -> f32{
         return self.mae;
     }
 
+    pub fn get_case_errors(&self) -> &[f32]{
+        &self.case_errors
+    }
+
+    pub fn get_output_errors(&self) -> Vec<f32>{
+        self.output_errors.clone()
+    }
+
+}
+
+fn squared_error(outputs: &[Vec<f32>], labels: &[Vec<f32>]) -> f64 {
+    let mut error = 0.;
+    for (output, label) in outputs.iter().zip(labels) {
+        for (x, y) in output.iter().zip(label) {
+            error += ((x - y) as f64).powi(2);
+        }
+    }
+    if error.is_nan() {
+        return f64::INFINITY;
+    }
+    error
+}
+
+// gaussian elimination with partial pivoting, None if the system is singular
+fn solve_linear_system(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for column in 0..n {
+        let pivot = (column..n).max_by(|x, y| a[*x][column].abs().total_cmp(&a[*y][column].abs()))?;
+        if a[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+        let pivot_row = a[column].clone();
+        for row in column + 1..n {
+            let factor = a[row][column] / pivot_row[column];
+            for (x, y) in a[row].iter_mut().zip(&pivot_row).skip(column) {
+                *x -= factor * y;
+            }
+            b[row] -= factor * b[column];
+        }
+    }
+    let mut x = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
 }
 
 fn get_argmax(nets: &Vec<f32>) -> usize {
@@ -288,3 +771,25 @@ fn get_argmax(nets: &Vec<f32>) -> usize {
         .map(|(index, _)| index)
         .unwrap()
 }
+
+// least-squares slope and intercept of labels on outputs (Keijzer, 2003);
+// constant outputs only get the label mean as offset
+fn fit_linear_scaling(outputs: &[f32], labels: &[f32]) -> (f32, f32) {
+    let n = outputs.len() as f32;
+    let output_mean = outputs.iter().sum::<f32>() / n;
+    let label_mean = labels.iter().sum::<f32>() / n;
+    let mut covariance = 0.;
+    let mut variance = 0.;
+    for (output, label) in outputs.iter().zip(labels) {
+        covariance += (output - output_mean) * (label - label_mean);
+        variance += (output - output_mean) * (output - output_mean);
+    }
+    if !covariance.is_finite() || !variance.is_finite() {
+        return (1., 0.);
+    }
+    if variance == 0. {
+        return (0., label_mean);
+    }
+    let slope = covariance / variance;
+    (slope, label_mean - slope * output_mean)
+}
@@ -0,0 +1,18 @@
+// value and derivative with respect to a single variable, for forward-mode
+// automatic differentiation of the real-valued graphs
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Dual {
+    pub value: f32,
+    pub derivative: f32,
+}
+
+impl Dual {
+    pub fn constant(value: f32) -> Dual {
+        Dual { value, derivative: 0. }
+    }
+
+    // the variable that is differentiated for
+    pub fn variable(value: f32) -> Dual {
+        Dual { value, derivative: 1. }
+    }
+}
@@ -0,0 +1,72 @@
+use ndarray::prelude::*;
+
+// samples as rows with every feature stored as one contiguous column, the
+// layout read by Chromosome::evaluate
+pub fn to_columns(rows: &[Vec<f32>]) -> Array2<f32> {
+    let nbr_features = rows.first().map_or(0, |x| x.len());
+    Array2::from_shape_fn((rows.len(), nbr_features).f(), |(i, j)| rows[i][j])
+}
+
+// the values of every node on every sample, one row of nbr_samples values per
+// node position, and the outputs gathered per sample; kept by the runner and
+// reused for all evaluations. Dual values share the layout for constant tuning
+#[derive(Clone, Default)]
+pub struct EvaluationBuffer<T = f32> {
+    values: Vec<T>,
+    outputs: Vec<Vec<T>>,
+    nbr_samples: usize,
+}
+
+impl EvaluationBuffer {
+    pub fn new() -> EvaluationBuffer {
+        EvaluationBuffer::default()
+    }
+}
+
+impl<T: Copy + Default> EvaluationBuffer<T> {
+
+    // only reallocates if the buffer has to grow
+    pub fn prepare(&mut self, nbr_nodes: usize, nbr_samples: usize, nbr_outputs: usize) {
+        self.nbr_samples = nbr_samples;
+        if self.values.len() < nbr_nodes * nbr_samples {
+            self.values.resize(nbr_nodes * nbr_samples, T::default());
+        }
+        if self.outputs.len() < nbr_samples {
+            self.outputs.resize(nbr_samples, Vec::new());
+        }
+        for out in self.outputs[..nbr_samples].iter_mut() {
+            out.resize(nbr_outputs, T::default());
+        }
+    }
+
+    pub fn node(&self, position: usize) -> &[T] {
+        &self.values[position * self.nbr_samples..(position + 1) * self.nbr_samples]
+    }
+
+    // the rows of all positions before the given one and the row of the position
+    pub fn split_at_node(&mut self, position: usize) -> (&[T], &mut [T]) {
+        let (before, rest) = self.values.split_at_mut(position * self.nbr_samples);
+        (before, &mut rest[..self.nbr_samples])
+    }
+
+    // one row of nbr_outputs values per sample
+    pub fn outputs(&self) -> &[Vec<T>] {
+        &self.outputs[..self.nbr_samples]
+    }
+
+    pub fn outputs_mut(&mut self) -> &mut [Vec<T>] {
+        &mut self.outputs[..self.nbr_samples]
+    }
+
+    // writes the row of the position into output k of every sample
+    pub fn gather_output(&mut self, k: usize, position: usize) {
+        let row = &self.values[position * self.nbr_samples..(position + 1) * self.nbr_samples];
+        for (out, value) in self.outputs.iter_mut().zip(row) {
+            out[k] = *value;
+        }
+    }
+
+    pub fn nbr_samples(&self) -> usize {
+        self.nbr_samples
+    }
+}
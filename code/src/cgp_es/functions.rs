@@ -0,0 +1,198 @@
+use crate::cgp_es::dual::Dual;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// a node function; unary functions ignore their second argument
+#[derive(Clone, Copy)]
+pub struct Function {
+    pub name: &'static str,
+    pub arity: usize,
+    pub apply: fn(f32, f32) -> f32,
+    // apply on whole slices, out[i] = apply(a[i], b[i])
+    pub kernel: fn(&[f32], &[f32], &mut [f32]),
+    // derivatives of apply with respect to both arguments
+    pub partials: fn(f32, f32) -> (f32, f32),
+    // pretty-print form, {0} and {1} are replaced by the arguments
+    pub format: &'static str,
+}
+
+// a Function whose scalar form and slice kernel share one expression, so that
+// both give the same values
+macro_rules! function {
+    ($name:expr, $arity:expr, |$a:ident, $b:ident| $apply:expr, $partials:expr, $format:expr) => {
+        Function {
+            name: $name,
+            arity: $arity,
+            apply: |$a, $b| $apply,
+            kernel: |a, b, out| {
+                for ((value, $a), $b) in out.iter_mut().zip(a).zip(b) {
+                    let ($a, $b) = (*$a, *$b);
+                    *value = $apply;
+                }
+            },
+            partials: $partials,
+            format: $format,
+        }
+    };
+}
+
+// every function that can be selected with --functions; the first 14 are the
+// default set, in the order of the old hard-coded function ids
+pub const FUNCTIONS: &[Function] = &[
+    function!("add", 2, |a, b| b + a, |_, _| (1., 1.), "({1} + {0})"),
+    function!("sub", 2, |a, b| b - a, |_, _| (-1., 1.), "({1} - {0})"),
+    function!("mul", 2, |a, b| b * a, |a, b| (b, a), "({1} * {0})"),
+    function!(
+        "div",
+        2,
+        |a, b| a / (b + 0.0000000001),
+        |a, b| (1. / (b + 0.0000000001), -a / (b + 0.0000000001).powi(2)),
+        "({0} / {1})"
+    ),
+    function!("neg", 1, |a, _b| -a, |_, _| (-1., 0.), "-{0}"),
+    function!("sin", 1, |a, _b| a.sin(), |a, _| (a.cos(), 0.), "sin({0})"),
+    function!("cos", 1, |a, _b| a.cos(), |a, _| (-a.sin(), 0.), "cos({0})"),
+    function!("tan", 1, |a, _b| a.tan(), |a, _| (1. + a.tan().powi(2), 0.), "tan({0})"),
+    function!("tanh", 1, |a, _b| a.tanh(), |a, _| (1. - a.tanh().powi(2), 0.), "tanh({0})"),
+    function!("relu", 1, |a, _b| if a <= 0. { 0. } else { a }, |a, _| (if a <= 0. { 0. } else { 1. }, 0.), "relu({0})"),
+    function!("exp", 1, |a, _b| a.exp(), |a, _| (a.exp(), 0.), "exp({0})"),
+    function!(
+        "log",
+        1,
+        |a, _b| if a <= 0. { 0. } else { a.ln() },
+        |a, _| (if a <= 0. { 0. } else { 1. / a }, 0.),
+        "log({0})"
+    ),
+    function!("abs", 1, |a, _b| a.abs(), |a, _| (sign(a), 0.), "|{0}|"),
+    function!(
+        "sigmoid",
+        1,
+        |a, _b| 1. / (1. + (-a).exp()),
+        |a, _| (1. / (1. + (-a).exp()) * (1. - 1. / (1. + (-a).exp())), 0.),
+        "sigmoid({0})"
+    ),
+    // protected like log: the square root of |x|
+    function!(
+        "sqrt",
+        1,
+        |a, _b| a.abs().sqrt(),
+        |a, _| (if a == 0. { 0. } else { sign(a) / (2. * a.abs().sqrt()) }, 0.),
+        "sqrt(|{0}|)"
+    ),
+    function!("square", 1, |a, _b| a * a, |a, _| (2. * a, 0.), "({0})^2"),
+    // |a|^b, so that fractional exponents stay real; at a = 0 both partials are
+    // 0 like at the kink of abs, b * |a|^(b - 1) would be inf * 0 for b < 1
+    function!(
+        "pow",
+        2,
+        |a, b| a.abs().powf(b),
+        |a, b| if a == 0. {
+            (0., 0.)
+        } else {
+            (b * a.abs().powf(b - 1.) * sign(a), a.abs().powf(b) * a.abs().ln())
+        },
+        "(|{0}| ^ {1})"
+    ),
+    function!("min", 2, |a, b| a.min(b), |a, b| if a <= b { (1., 0.) } else { (0., 1.) }, "min({0}, {1})"),
+    function!("max", 2, |a, b| a.max(b), |a, b| if a >= b { (1., 0.) } else { (0., 1.) }, "max({0}, {1})"),
+];
+
+const DEFAULT_SET_SIZE: usize = 14;
+
+// the functions available to the computational nodes; node function ids index
+// into this list
+#[derive(Clone)]
+pub struct FunctionSet {
+    pub functions: Vec<Function>,
+}
+
+impl Default for FunctionSet {
+    fn default() -> Self {
+        FunctionSet { functions: FUNCTIONS[..DEFAULT_SET_SIZE].to_vec() }
+    }
+}
+
+impl FunctionSet {
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    pub fn arity(&self, function_id: usize) -> usize {
+        self.functions[function_id].arity
+    }
+
+    pub fn apply(&self, function_id: usize, a: f32, b: f32) -> f32 {
+        (self.functions[function_id].apply)(a, b)
+    }
+
+    pub fn apply_slices(&self, function_id: usize, a: &[f32], b: &[f32], out: &mut [f32]) {
+        (self.functions[function_id].kernel)(a, b, out)
+    }
+
+    // chain rule through the function; a zero derivative is not multiplied, so
+    // that an infinite partial does not turn it into NaN
+    pub fn apply_dual(&self, function_id: usize, a: Dual, b: Dual) -> Dual {
+        let function = &self.functions[function_id];
+        let (partial_a, partial_b) = (function.partials)(a.value, b.value);
+        let mut derivative = 0.;
+        if a.derivative != 0. {
+            derivative += partial_a * a.derivative;
+        }
+        if b.derivative != 0. && function.arity == 2 {
+            derivative += partial_b * b.derivative;
+        }
+        Dual { value: (function.apply)(a.value, b.value), derivative }
+    }
+
+    pub fn format(&self, function_id: usize, a: &str, b: &str) -> String {
+        self.functions[function_id].format.replace("{0}", a).replace("{1}", b)
+    }
+
+    // ids of the functions with the given arity, in set order
+    pub fn ids_with_arity(&self, arity: usize) -> Vec<usize> {
+        (0..self.len()).filter(|&x| self.arity(x) == arity).collect()
+    }
+}
+
+// derivative of |x|, 0 at the kink
+fn sign(x: f32) -> f32 {
+    if x == 0. {
+        0.
+    } else {
+        x.signum()
+    }
+}
+
+impl FromStr for FunctionSet {
+    type Err = String;
+
+    // "default" or comma separated names, e.g. add,sub,mul,div
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "default" {
+            return Ok(FunctionSet::default());
+        }
+        let mut functions = Vec::new();
+        for name in s.split(',').map(|x| x.trim()) {
+            let function = FUNCTIONS.iter().find(|x| x.name == name).ok_or_else(|| {
+                let names: Vec<&str> = FUNCTIONS.iter().map(|x| x.name).collect();
+                format!("unknown function '{}', expected default or some of {}", name, names.join(","))
+            })?;
+            if functions.iter().any(|x: &Function| x.name == name) {
+                return Err(format!("function '{}' is listed twice", name));
+            }
+            functions.push(*function);
+        }
+        Ok(FunctionSet { functions })
+    }
+}
+
+impl Display for FunctionSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self.functions.iter().map(|x| x.name).collect();
+        write!(f, "{}", names.join(","))
+    }
+}
@@ -1,2 +1,6 @@
 pub mod chromosome;
-pub mod node;
\ No newline at end of file
+pub mod dual;
+pub mod evaluator;
+pub mod functions;
+pub mod model;
+pub mod node;
@@ -0,0 +1,100 @@
+use crate::cgp_es::chromosome::Chromosome;
+use crate::cgp_es::evaluator::{self, EvaluationBuffer};
+use crate::datasets::preprocessing::Preprocessor;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// increased whenever the JSON layout of Model, Chromosome, Node,
+// CgpParameters or Preprocessor changes; older files are rejected on load
+pub const FORMAT_VERSION: u32 = 1;
+
+// an evolved chromosome with the preprocessing fitted on its training data,
+// everything needed to apply it to new raw data
+#[derive(Serialize, Deserialize)]
+pub struct Model {
+    pub version: u32,
+    pub dataset: String,
+    pub seed: u64,
+    pub repetition: usize,
+    pub chromosome: Chromosome,
+    pub preprocessor: Preprocessor,
+}
+
+#[derive(Debug)]
+pub enum ModelError {
+    Io { path: PathBuf, source: io::Error },
+    Json { path: PathBuf, source: serde_json::Error },
+    Version { path: PathBuf, found: u32 },
+}
+
+impl Display for ModelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelError::Io { path, source } => write!(f, "cannot access {}: {}", path.display(), source),
+            ModelError::Json { path, source } => write!(f, "{}: {}", path.display(), source),
+            ModelError::Version { path, found } => {
+                write!(f, "{}: format version {}, expected {}", path.display(), found, FORMAT_VERSION)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ModelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ModelError::Io { source, .. } => Some(source),
+            ModelError::Json { source, .. } => Some(source),
+            ModelError::Version { .. } => None,
+        }
+    }
+}
+
+// only the version, read first so that a file of another version is reported
+// as such and not as a missing or unexpected field
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl Model {
+    pub fn new(chromosome: Chromosome, preprocessor: Preprocessor, dataset: &str, seed: u64, repetition: usize) -> Model {
+        Model { version: FORMAT_VERSION, dataset: dataset.to_string(), seed, repetition, chromosome, preprocessor }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ModelError> {
+        let io_error = |source| ModelError::Io { path: path.to_path_buf(), source };
+        let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+        serde_json::to_writer_pretty(&mut writer, self)
+            .map_err(|source| ModelError::Json { path: path.to_path_buf(), source })?;
+        writeln!(writer).map_err(io_error)?;
+        writer.flush().map_err(io_error)
+    }
+
+    pub fn load(path: &Path) -> Result<Model, ModelError> {
+        let json_error = |source| ModelError::Json { path: path.to_path_buf(), source };
+        let text = fs::read_to_string(path).map_err(|source| ModelError::Io { path: path.to_path_buf(), source })?;
+        let header: Header = serde_json::from_str(&text).map_err(json_error)?;
+        if header.version != FORMAT_VERSION {
+            return Err(ModelError::Version { path: path.to_path_buf(), found: header.version });
+        }
+        let mut model: Model = serde_json::from_str(&text).map_err(json_error)?;
+        // the skipped fields: active nodes follow from the genes, the errors
+        // are filled again by the next evaluation
+        model.chromosome.get_active_nodes_id();
+        model.chromosome.output_errors = vec![0.; model.chromosome.output_node_ids.len()];
+        Ok(model)
+    }
+
+    // outputs for raw rows, in the units of the original labels
+    pub fn predict(&mut self, data: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        let mut data = data.to_vec();
+        self.preprocessor.imputer.transform(&mut data);
+        self.preprocessor.scaler.transform(&mut data);
+        let mut outputs = self.chromosome.predict(&evaluator::to_columns(&data), &mut EvaluationBuffer::new());
+        self.preprocessor.target_scaler.inverse_transform(&mut outputs);
+        outputs
+    }
+}
@@ -1,223 +1,178 @@
+use crate::cgp_es::dual::Dual;
+use crate::cgp_es::functions::FunctionSet;
 use crate::utils::node_type::NodeType;
+use crate::utils::random::CgpRng;
+use rayon::prelude::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::ops::Range;
 use usize;
 
-#[derive(Clone)]
+// rows per parallel task in execute
+pub const ROW_CHUNK_SIZE: usize = 8192;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Node {
     pub position: usize,
     pub node_type: NodeType,
     pub nbr_inputs: usize,
     pub graph_width: usize,
+    // grid geometry of the computational nodes, see is_valid_connection
+    pub rows: usize,
+    pub levels_back: usize,
+    // size of the function set, see functions::FunctionSet
+    pub nbr_functions: usize,
     pub function_id: usize,
     pub connection1: usize,
     pub connection2: usize,
 }
 
-impl Display for Node {
+// a node printed with the name of its function, see Node::display
+pub struct NodeDisplay<'a> {
+    node: &'a Node,
+    functions: &'a FunctionSet,
+}
+
+impl Display for NodeDisplay<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Node Pos: {}, ", self.position)?;
-        write!(f, "Node Type: {}, ", self.node_type)?;
-        match self.function_id {
-            0 => {
-                write!(f, "Func: AND, ")?;
-            }
-            1 => {
-                write!(f, "Func: OR, ")?;
-            }
-            2 => {
-                write!(f, "Func: NAND, ")?;
-            }
-            3 => {
-                write!(f, "Func: NOR, ")?;
-            }
-            _ => panic!(),
-        };
+        write!(f, "Node Pos: {}, ", self.node.position)?;
+        write!(f, "Node Type: {}, ", self.node.node_type)?;
+        write!(f, "Func: {}, ", self.functions.functions[self.node.function_id].name)?;
         return writeln!(
             f,
             "Connections: ({}, {}), ",
-            self.connection1, self.connection2
+            self.node.connection1, self.node.connection2
         );
     }
 }
 
 impl Node {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         position: usize,
         nbr_inputs: usize,
         graph_width: usize,
+        rows: usize,
+        levels_back: usize,
+        nbr_functions: usize,
         node_type: NodeType,
+        rng: &mut CgpRng,
     ) -> Self {
-        let function_id = rand::thread_rng().gen_range(0..=13) as usize;
-        let connection1: usize;
-        let connection2: usize;
-
-        match node_type {
-            NodeType::InputNode => {
-                connection1 = usize::MAX;
-                connection2 = usize::MAX;
-            }
-            NodeType::ComputationalNode => {
-                connection1 = rand::thread_rng().gen_range(0..=position - 1) as usize;
-                connection2 = rand::thread_rng().gen_range(0..=position - 1) as usize;
-            }
-            NodeType::OutputNode => {
-                connection1 =
-                    rand::thread_rng().gen_range(0..=nbr_inputs + graph_width - 1) as usize;
-                connection2 = usize::MAX;
-            }
-        }
-
-        Self {
+        let function_id = rng.gen_range(0..nbr_functions);
+        let mut node = Self {
             position,
             node_type,
             nbr_inputs,
             graph_width,
+            rows,
+            levels_back,
+            nbr_functions,
             function_id,
-            connection1,
-            connection2,
+            connection1: usize::MAX,
+            connection2: usize::MAX,
+        };
+
+        if node.node_type != NodeType::InputNode {
+            let candidates = node.connection_range();
+            node.connection1 = rng.gen_range(candidates.clone());
+            if node.node_type == NodeType::ComputationalNode {
+                node.connection2 = rng.gen_range(candidates);
+            }
         }
+        node
     }
 
-    pub fn execute(
-        &self,
-        conn1_value: &Vec<f32>,
-        conn2_value: &Vec<f32>,
-    ) -> Vec<f32> {
-        assert!(self.node_type != NodeType::InputNode);
-        let mut res= vec![0.; conn1_value.len()];
-        match self.function_id {
-            0 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn2_value[i] + conn1_value[i];
-                }
-                return res;
-            },
-            1 => {
-                    for i in 0..conn1_value.len(){
-                        res[i] = conn2_value[i] - conn1_value[i];
-                    }
-                    return res;
-                },
-            2 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn2_value[i] * conn1_value[i];
-                }
-                return res;
-            },
-            3 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn1_value[i] / (conn2_value[i] + 0.0000000001);
-                }
-                return res;
-            },
-            4 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn1_value[i] * -1.;
-                }
-                return res;
-            },
-            5 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn1_value[i].sin();
-                }
-                return res;
-            },
-            6 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn1_value[i].cos();
-                }
-                return res;
-            },
-            7 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn1_value[i].tan();
-                }
-                return res;
-            },
-            8 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn1_value[i].tanh();
-                }
-                return res;
-            },
-            9 => {
-                for i in 0..conn1_value.len(){
-                    if conn1_value[i] <= 0. {
-                        res[i] = 0.;
-                    }else{
-                        res[i] = conn1_value[i];
-                    }
-                }
-                return res;
-            },
-            10 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn1_value[i].exp();
-                }
-                return res;
-            },
-            11 => {
-                for i in 0..conn1_value.len(){
-                    if conn1_value[i] <= 0. {
-                        res[i] = 0.;
-                    }else{
-                        res[i] = conn1_value[i].ln();
-                    };
-                }
-                return res;
-            },
-            12 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = conn1_value[i].abs();
-                }
-                return res;
-            },
-            13 => {
-                for i in 0..conn1_value.len(){
-                    res[i] = 1. / (1. + (-conn1_value[i]).exp())
+    // the function printed by name, which the node itself does not know
+    pub fn display<'a>(&'a self, functions: &'a FunctionSet) -> NodeDisplay<'a> {
+        NodeDisplay { node: self, functions }
+    }
+
+    // positions the node may read from; output nodes can use every input and
+    // computational node. The valid connections of a computational node are
+    // always contiguous: the levels_back columns before its own, which include
+    // the inputs while those are in reach
+    pub fn connection_range(&self) -> Range<usize> {
+        match self.node_type {
+            NodeType::InputNode => 0..0,
+            NodeType::OutputNode => 0..self.nbr_inputs + self.graph_width,
+            NodeType::ComputationalNode => {
+                let column = (self.position - self.nbr_inputs) / self.rows;
+                let end = self.nbr_inputs + column * self.rows;
+                if self.levels_back == 0 || column < self.levels_back {
+                    0..end
+                } else {
+                    end - self.levels_back * self.rows..end
                 }
-                return res;
-            },
-            _ => panic!("wrong function id: {}", self.function_id),
+            }
+        }
+    }
+
+    // writes the function of the connection values into out; large datasets are
+    // split into chunks of rows that run in parallel, the functions are element-wise
+    // so this gives the same values
+    pub fn execute(&self, functions: &FunctionSet, conn1_value: &[f32], conn2_value: &[f32], out: &mut [f32]) {
+        assert!(self.node_type != NodeType::InputNode);
+        if out.len() < 2 * ROW_CHUNK_SIZE {
+            functions.apply_slices(self.function_id, conn1_value, conn2_value, out);
+            return;
         }
+        out.par_chunks_mut(ROW_CHUNK_SIZE)
+            .zip(conn1_value.par_chunks(ROW_CHUNK_SIZE))
+            .zip(conn2_value.par_chunks(ROW_CHUNK_SIZE))
+            .for_each(|((out, a), b)| functions.apply_slices(self.function_id, a, b, out));
     }
 
+    pub fn execute_dual(&self, functions: &FunctionSet, conn1_value: &[Dual], conn2_value: &[Dual], out: &mut [Dual]) {
+        assert!(self.node_type != NodeType::InputNode);
+        for ((value, a), b) in out.iter_mut().zip(conn1_value).zip(conn2_value) {
+            *value = functions.apply_dual(self.function_id, *a, *b);
+        }
+    }
 
-    pub fn mutate(&mut self) {
+    pub fn mutate(&mut self, rng: &mut CgpRng) {
         assert!(self.node_type != NodeType::InputNode);
 
         match self.node_type {
-            NodeType::OutputNode => self.mutate_output_node(),
-            NodeType::ComputationalNode => self.mutate_computational_node(),
+            NodeType::OutputNode => self.mutate_output_node(rng),
+            NodeType::ComputationalNode => self.mutate_computational_node(rng),
             _ => {
                 panic!("Trying to mutate input node")
             }
         }
     }
 
-    fn mutate_connection(connection: &mut usize, upper_range: usize) {
-        *connection = gen_random_number(*connection, upper_range);
+    // another of the candidates, if there is one
+    fn mutate_connection(connection: &mut usize, candidates: Range<usize>, rng: &mut CgpRng) {
+        let current = connection.checked_sub(candidates.start).unwrap_or(usize::MAX);
+        *connection = candidates.start + gen_random_number(current, candidates.len(), rng);
     }
 
-    fn mutate_function(&mut self) {
-        self.function_id = gen_random_number(self.function_id, 14);
+    fn mutate_function(&mut self, rng: &mut CgpRng) {
+        self.function_id = gen_random_number(self.function_id, self.nbr_functions, rng);
     }
 
-    fn mutate_output_node(&mut self) {
-        Node::mutate_connection(&mut self.connection1, self.graph_width + self.nbr_inputs);
+    fn mutate_output_node(&mut self, rng: &mut CgpRng) {
+        let candidates = self.connection_range();
+        Node::mutate_connection(&mut self.connection1, candidates, rng);
 
         assert!(self.connection1 < self.position);
     }
 
-    fn mutate_computational_node(&mut self) {
-        let rand_nbr = rand::thread_rng().gen_range(0..=2);
+    fn mutate_computational_node(&mut self, rng: &mut CgpRng) {
+        let rand_nbr = rng.gen_range(0..=2);
         match rand_nbr {
-            0 => Node::mutate_connection(&mut self.connection1, self.position),
+            0 => {
+                let candidates = self.connection_range();
+                Node::mutate_connection(&mut self.connection1, candidates, rng)
+            }
 
-            1 => Node::mutate_connection(&mut self.connection2, self.position),
+            1 => {
+                let candidates = self.connection_range();
+                Node::mutate_connection(&mut self.connection2, candidates, rng)
+            }
 
-            2 => self.mutate_function(),
+            2 => self.mutate_function(rng),
 
             _ => {
                 panic!("Mutation: output node something wrong")
@@ -230,12 +185,25 @@ impl Node {
 
 }
 
-fn gen_random_number(excluded: usize, upper_range: usize) -> usize {
+// classic cgp geometry: the computational nodes fill a grid column by column
+// with rows nodes each, and a node reads from the levels_back previous columns;
+// the inputs count as the column before the first one; levels_back 0 is no limit
+pub fn is_valid_connection(position: usize, connection: usize, nbr_inputs: usize, rows: usize, levels_back: usize) -> bool {
+    let levels_back = if levels_back == 0 { usize::MAX } else { levels_back };
+    let column = (position - nbr_inputs) / rows;
+    if connection < nbr_inputs {
+        return column < levels_back;
+    }
+    let connection_column = (connection - nbr_inputs) / rows;
+    connection_column < column && column - connection_column <= levels_back
+}
+
+fn gen_random_number(excluded: usize, upper_range: usize, rng: &mut CgpRng) -> usize {
     if upper_range <= 1 {
         return 0;
     }
     loop {
-        let rand_nbr: usize = rand::thread_rng().gen_range(0..=upper_range - 1);
+        let rand_nbr: usize = rng.gen_range(0..=upper_range - 1);
         if rand_nbr != excluded {
             return rand_nbr;
         }
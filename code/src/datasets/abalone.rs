@@ -1,55 +1,28 @@
-use std::fs::File;
-use std::io::{self, BufRead};
+use crate::datasets::loader::{self, DatasetError, DatasetSchema, Encoding, Samples, Target};
+use std::path::Path;
 
-pub fn get_dataset() -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
-    let file = File::open("/data/oc-compute03/trautwju/Masterarbeit/src/datasets/Data/Regression/abalone.data").expect("Couldn't open input");
-    let mut csv_file = csv::Reader::from_reader(file);
-    let data_len = csv_file.records().count();
-    let mut data = vec![vec![0.; 8]; data_len + 1];
-    let mut labels = vec![vec![0.; 1]; data_len + 1];
-    let mut mins = vec![100000000000.; 8];
-    let mut maxs = vec![-10000000000.; 8];
-    let mut i = 0;
-    let mut standardisation_labels:Vec<f32> = vec![0.; data_len + 1];
-    let file = File::open("/data/oc-compute03/trautwju/Masterarbeit/src/datasets/Data/Regression/abalone.data").expect("Couldn't open input");
-    let reader = io::BufReader::new(file);
-    for line in reader.lines() {
-        let record = line.unwrap();
-        let vector:Vec<&str> = record.split(",").collect();
-        data[i][0] = match vector[0].trim() {
-            "M" => 0.0,
-            "F" => 0.5,
-            "I" => 1.0,
-            _ => -1.,
-        };
-        for j in 1..8{
-            data[i][j] = vector[j].trim().parse::<f32>().unwrap();
-        }
-        labels[i][0] = vector[8].trim().parse::<f32>().unwrap();
-        standardisation_labels[i] = labels[i][0];
-        for j in 0..8{
-            if data[i][j] > maxs[j]{
-                maxs[j] = data[i][j]
-            }
-            if data[i][j] < mins[j]{
-                mins[j] = data[i][j]
-            }
-        }
-        i += 1;
-    }
-    let mean:f32 = standardisation_labels.iter().sum::<f32>() / standardisation_labels.len() as f32;
-    let mut deviation:f32 = standardisation_labels
-        .iter()
-        .map(|&x| (x - mean).powi(2))
-        .sum::<f32>();
-    deviation /= standardisation_labels.len() as f32;
-    deviation = deviation.sqrt();
+// the file has no header row
+const COLUMNS: &[&str] = &[
+    "Sex", "Length", "Diameter", "Height", "Whole_weight", "Shucked_weight", "Viscera_weight",
+    "Shell_weight", "Rings",
+];
+const SEX: &[(&str, f32)] = &[("M", 0.0), ("F", 0.5), ("I", 1.0)];
 
-    for i in 0..data.len(){
-        for j in 0..data[0].len(){
-            data[i][j] = (data[i][j] - mins[j]) / (maxs[j] - mins[j])
-        }
-        labels[i][0] = (labels[i][0] - mean) / deviation;
+pub fn schema() -> DatasetSchema {
+    let mut features = vec![loader::column(0, Encoding::Mapped(SEX))];
+    features.extend(loader::numeric(1..8));
+    DatasetSchema {
+        file_name: "Regression/abalone.data",
+        delimiter: ',',
+        header_rows: 0,
+        column_names: COLUMNS,
+        missing_token: None,
+        strip_quotes: false,
+        features,
+        target: Target::Regression(8),
     }
-    return (data, labels);
-}
\ No newline at end of file
+}
+
+pub fn get_dataset(data_root: &Path) -> Result<Samples, DatasetError> {
+    loader::load(&schema(), data_root)
+}
@@ -0,0 +1,284 @@
+use crate::datasets::loader::{DatasetError, Samples, MISSING_VALUE};
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeType {
+    Numeric,
+    // declared values, unquoted
+    Nominal(Vec<String>),
+    String,
+    Date,
+}
+
+#[derive(Clone, Debug)]
+pub struct Attribute {
+    pub name: String,
+    pub kind: AttributeType,
+}
+
+impl Attribute {
+    // index of a nominal value; falls back to a whitespace-insensitive match
+    // because some UCI files pad their values inconsistently
+    pub fn nominal_index(&self, value: &str) -> Option<usize> {
+        let values = match &self.kind {
+            AttributeType::Nominal(values) => values,
+            _ => return None,
+        };
+        if let Some(index) = values.iter().position(|x| x == value) {
+            return Some(index);
+        }
+        let value = value.trim();
+        values.iter().position(|x| x.trim() == value)
+    }
+}
+
+pub struct ArffFile {
+    pub relation: String,
+    pub attributes: Vec<Attribute>,
+    // None marks a missing value ('?')
+    pub rows: Vec<Vec<Option<String>>>,
+    // line in the file each row was read from
+    pub row_lines: Vec<usize>,
+}
+
+impl ArffFile {
+    // the attribute called "class" if there is one, otherwise the last attribute
+    pub fn default_class_index(&self) -> usize {
+        self.attributes
+            .iter()
+            .position(|x| x.name.eq_ignore_ascii_case("class"))
+            .unwrap_or(self.attributes.len() - 1)
+    }
+
+    pub fn class_names(&self, class_index: usize) -> Option<&[String]> {
+        match &self.attributes[class_index].kind {
+            AttributeType::Nominal(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    // numeric and nominal attributes apart from the class are used as features,
+    // string and date attributes are skipped
+    pub fn feature_indices(&self, class_index: usize) -> Vec<usize> {
+        (0..self.attributes.len())
+            .filter(|&i| i != class_index)
+            .filter(|&i| matches!(self.attributes[i].kind, AttributeType::Numeric | AttributeType::Nominal(_)))
+            .collect()
+    }
+}
+
+pub fn parse(path: &Path) -> Result<ArffFile, DatasetError> {
+    let file = File::open(path).map_err(|source| DatasetError::Io { path: path.to_path_buf(), source })?;
+    let reader = io::BufReader::new(file);
+
+    let mut relation = String::new();
+    let mut attributes: Vec<Attribute> = Vec::new();
+    let mut rows: Vec<Vec<Option<String>>> = Vec::new();
+    let mut row_lines: Vec<usize> = Vec::new();
+    let mut in_data = false;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line_nbr = i + 1;
+        let record = line.map_err(|source| DatasetError::Io { path: path.to_path_buf(), source })?;
+        let record = record.trim();
+        if record.is_empty() || record.starts_with('%') {
+            continue;
+        }
+        let malformed = |message: &str| DatasetError::Malformed {
+            path: path.to_path_buf(),
+            line: line_nbr,
+            message: message.to_string(),
+        };
+
+        if in_data {
+            if record.starts_with('{') {
+                return Err(malformed("sparse instances are not supported"));
+            }
+            let fields = split_fields(record).ok_or_else(|| malformed("unterminated quote"))?;
+            if fields.len() != attributes.len() {
+                return Err(malformed(&format!(
+                    "expected {} values, found {}",
+                    attributes.len(),
+                    fields.len()
+                )));
+            }
+            rows.push(fields);
+            row_lines.push(line_nbr);
+            continue;
+        }
+
+        let (keyword, rest) = split_keyword(record);
+        if keyword.eq_ignore_ascii_case("@relation") {
+            relation = unquote(rest.trim()).to_string();
+        } else if keyword.eq_ignore_ascii_case("@attribute") {
+            let attribute = parse_attribute(rest).map_err(|x| malformed(&x))?;
+            attributes.push(attribute);
+        } else if keyword.eq_ignore_ascii_case("@data") {
+            if attributes.is_empty() {
+                return Err(malformed("@data before any @attribute"));
+            }
+            in_data = true;
+        } else {
+            return Err(malformed(&format!("unexpected '{}' in header", keyword)));
+        }
+    }
+
+    if rows.is_empty() {
+        return Err(DatasetError::Empty { path: path.to_path_buf() });
+    }
+    Ok(ArffFile { relation, attributes, rows, row_lines })
+}
+
+// the class attribute is one-hot encoded in the order of its declaration
+pub fn load(path: &Path, class_attribute: Option<&str>) -> Result<Samples, DatasetError> {
+    let arff = parse(path)?;
+    let class_name = class_attribute.unwrap_or_else(|| &arff.attributes[arff.default_class_index()].name);
+    let class_index = arff.attributes.iter().position(|x| x.name == class_name).ok_or_else(|| {
+        DatasetError::UnknownAttribute { path: path.to_path_buf(), name: class_name.to_string() }
+    })?;
+    let classes = arff.class_names(class_index).ok_or_else(|| DatasetError::ClassAttribute {
+        path: path.to_path_buf(),
+        name: class_name.to_string(),
+    })?;
+    let feature_indices = arff.feature_indices(class_index);
+
+    let mut data: Vec<Vec<f32>> = Vec::with_capacity(arff.rows.len());
+    let mut labels: Vec<Vec<f32>> = Vec::with_capacity(arff.rows.len());
+    for (i, fields) in arff.rows.iter().enumerate() {
+        let mut row = Vec::with_capacity(feature_indices.len());
+        for &j in &feature_indices {
+            row.push(encode(&arff.attributes[j], fields[j].as_deref()).ok_or_else(|| {
+                DatasetError::InvalidValue {
+                    path: path.to_path_buf(),
+                    line: arff.row_lines[i],
+                    column: j,
+                    value: fields[j].clone().unwrap_or_default(),
+                }
+            })?);
+        }
+        data.push(row);
+
+        let value = fields[class_index].as_deref().unwrap_or("?");
+        let class = arff.attributes[class_index].nominal_index(value).ok_or_else(|| {
+            DatasetError::UnknownClass { path: path.to_path_buf(), line: arff.row_lines[i], value: value.to_string() }
+        })?;
+        let mut one_hot = vec![0.; classes.len()];
+        one_hot[class] = 1.;
+        labels.push(one_hot);
+    }
+    Ok((data, labels))
+}
+
+pub fn load_from_root(data_root: &Path, file_name: &str) -> Result<Samples, DatasetError> {
+    load(&data_root.join(file_name), None)
+}
+
+fn encode(attribute: &Attribute, value: Option<&str>) -> Option<f32> {
+    let value = match value {
+        Some(x) => x,
+        None => return Some(MISSING_VALUE),
+    };
+    match attribute.kind {
+        AttributeType::Numeric => value.trim().parse::<f32>().ok(),
+        // values outside the declared set are treated as missing
+        AttributeType::Nominal(_) => Some(attribute.nominal_index(value).map_or(MISSING_VALUE, |x| x as f32)),
+        AttributeType::String | AttributeType::Date => None,
+    }
+}
+
+fn parse_attribute(declaration: &str) -> Result<Attribute, String> {
+    let declaration = declaration.trim();
+    let (name, rest) = match declaration.chars().next() {
+        Some(quote @ ('\'' | '"')) => {
+            let end = declaration[1..]
+                .find(quote)
+                .ok_or_else(|| "unterminated attribute name".to_string())?;
+            (&declaration[1..end + 1], &declaration[end + 2..])
+        }
+        _ => split_keyword(declaration),
+    };
+    let rest = rest.trim();
+    if name.is_empty() || rest.is_empty() {
+        return Err("attribute declaration needs a name and a type".to_string());
+    }
+
+    if rest.starts_with('{') {
+        let end = rest.rfind('}').ok_or_else(|| "unterminated nominal value set".to_string())?;
+        let values = split_fields(&rest[1..end]).ok_or_else(|| "unterminated quote".to_string())?;
+        let values: Vec<String> = values.into_iter().map(|x| x.unwrap_or_else(|| "?".to_string())).collect();
+        return Ok(Attribute { name: name.to_string(), kind: AttributeType::Nominal(values) });
+    }
+
+    let (kind, _) = split_keyword(rest);
+    let kind = match kind.to_ascii_lowercase().as_str() {
+        "numeric" | "real" | "integer" => AttributeType::Numeric,
+        "string" => AttributeType::String,
+        // the optional date format is ignored, dates are never used as features
+        "date" => AttributeType::Date,
+        other => return Err(format!("unsupported attribute type '{}'", other)),
+    };
+    Ok(Attribute { name: name.to_string(), kind })
+}
+
+fn split_keyword(record: &str) -> (&str, &str) {
+    match record.find(char::is_whitespace) {
+        Some(i) => (&record[..i], &record[i..]),
+        None => (record, ""),
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    let bytes = value.as_bytes();
+    if value.len() >= 2 && (bytes[0] == b'\'' || bytes[0] == b'"') && bytes[value.len() - 1] == bytes[0] {
+        return &value[1..value.len() - 1];
+    }
+    value
+}
+
+// splits a comma separated record, honouring single and double quotes and
+// backslash escapes inside quotes; unquoted '?' becomes None
+fn split_fields(record: &str) -> Option<Vec<Option<String>>> {
+    let mut fields = Vec::new();
+    let mut chars = record.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|x| x.is_whitespace()) {
+            chars.next();
+        }
+        let mut value = String::new();
+        let mut quoted = false;
+        if let Some(&quote @ ('\'' | '"')) = chars.peek() {
+            quoted = true;
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '\\' => value.push(chars.next()?),
+                    x if x == quote => break,
+                    x => value.push(x),
+                }
+            }
+            while chars.peek().is_some_and(|&x| x != ',') {
+                chars.next();
+            }
+        } else {
+            while let Some(&x) = chars.peek() {
+                if x == ',' {
+                    break;
+                }
+                value.push(x);
+                chars.next();
+            }
+            value = value.trim_end().to_string();
+        }
+
+        if !quoted && value == "?" {
+            fields.push(None);
+        } else {
+            fields.push(Some(value));
+        }
+        if chars.next().is_none() {
+            return Some(fields);
+        }
+    }
+}
@@ -1,55 +1,27 @@
-use std::fs::File;
-use std::io::{self, BufRead};
+use crate::datasets::loader::{self, DatasetError, DatasetSchema, Encoding, Samples, Target};
+use std::path::Path;
 
-pub fn get_dataset() -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
-    let file = File::open("/data/oc-compute03/trautwju/Masterarbeit/src/datasets/Data/Regression/bike_sharing_hour.csv").expect("Couldn't open input");
-    let mut csv_file = csv::Reader::from_reader(file);
-    let data_len = csv_file.records().count();
-    let mut data = vec![vec![0.; 15]; data_len];
-    let mut labels = vec![vec![0.; 1]; data_len];
-    let mut standardisation_labels:Vec<f32> = vec![0.; data_len + 1];
-    let mut mins = vec![100000000000.; 15];
-    let mut maxs = vec![-10000000000.; 15];
-    let mut i = 0;
-    let file = File::open("/data/oc-compute03/trautwju/Masterarbeit/src/datasets/Data/Regression/bike_sharing_hour.csv").expect("Couldn't open input");
-    let reader = io::BufReader::new(file);
-    for line in reader.lines() {
-        let record = line.unwrap();
-        let vector:Vec<&str> = record.split(",").collect();
-        if vector[0] == "instant"{
-            continue;
-        }
-        data[i][0] = ((vector[0].trim().parse::<f32>().unwrap() - 1.) / 24.).floor();
-        for j in 2..16{
-            data[i][j-1] = vector[j].trim().parse::<f32>().unwrap();
-        }
-        labels[i][0] = vector[16].trim().parse::<f32>().unwrap();
-        standardisation_labels[i] = labels[i][0];
-        for j in 0..15{
-            if data[i][j] > maxs[j]{
-                maxs[j] = data[i][j]
-            }
-            if data[i][j] < mins[j]{
-                mins[j] = data[i][j]
-            }
-        }
-        i += 1;
-    }
-
-    let mean:f32 = standardisation_labels.iter().sum::<f32>() / standardisation_labels.len() as f32;
-    let mut deviation:f32 = standardisation_labels
-        .iter()
-        .map(|&x| (x - mean).powi(2))
-        .sum::<f32>();
-    deviation /= standardisation_labels.len() as f32;
-    deviation = deviation.sqrt();
+// the hourly records are numbered consecutively, 24 per day
+fn day_of_instant(value: &str) -> Option<f32> {
+    let instant = value.parse::<f32>().ok()?;
+    Some(((instant - 1.) / 24.).floor())
+}
 
-    for i in 0..data.len(){
-        for j in 0..data[0].len(){
-            data[i][j] = (data[i][j] - mins[j]) / (maxs[j] - mins[j])
-        }
-        labels[i][0] = (labels[i][0] - mean) / deviation;
+pub fn schema() -> DatasetSchema {
+    let mut features = vec![loader::column(0, Encoding::Derived(day_of_instant))];
+    features.extend(loader::numeric(2..16));
+    DatasetSchema {
+        file_name: "Regression/bike_sharing_hour.csv",
+        delimiter: ',',
+        header_rows: 1,
+        column_names: &[],
+        missing_token: None,
+        strip_quotes: false,
+        features,
+        target: Target::Regression(16),
     }
+}
 
-    return (data, labels);
-}
\ No newline at end of file
+pub fn get_dataset(data_root: &Path) -> Result<Samples, DatasetError> {
+    loader::load(&schema(), data_root)
+}
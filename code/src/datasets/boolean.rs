@@ -0,0 +1,97 @@
+use ndarray::prelude::*;
+
+// (inputs, labels) with one row per truth table line, as expected by the
+// bool-valued chromosomes (vanilla_cgp, dag, reorder)
+pub type BooleanSamples = (Array2<bool>, Array2<bool>);
+
+// 2^20 rows; find rejects larger problems, which also keeps the shifts below
+// from overflowing
+pub const MAX_INPUTS: usize = 20;
+
+// all 2^nbr_inputs input combinations; row i holds the bits of i with the most
+// significant bit in column 0, and the outputs are the bits of function(i),
+// also most significant first
+fn truth_table(nbr_inputs: usize, nbr_outputs: usize, function: impl Fn(usize) -> usize) -> BooleanSamples {
+    let nbr_rows = 1 << nbr_inputs;
+    let inputs = Array2::from_shape_fn((nbr_rows, nbr_inputs), |(row, column)| {
+        (row >> (nbr_inputs - 1 - column)) & 1 == 1
+    });
+    let outputs: Vec<usize> = (0..nbr_rows).map(function).collect();
+    let labels = Array2::from_shape_fn((nbr_rows, nbr_outputs), |(row, column)| {
+        (outputs[row] >> (nbr_outputs - 1 - column)) & 1 == 1
+    });
+    (inputs, labels)
+}
+
+// true if the number of set inputs is even
+pub fn even_parity(nbr_inputs: usize) -> BooleanSamples {
+    truth_table(nbr_inputs, 1, |x| if x.count_ones() % 2 == 0 { 1 } else { 0 })
+}
+
+// address bits first, then 2^address_bits data bits; outputs the addressed data bit
+pub fn multiplexer(address_bits: usize) -> BooleanSamples {
+    let nbr_data = 1 << address_bits;
+    truth_table(address_bits + nbr_data, 1, |x| {
+        let address = x >> nbr_data;
+        (x >> (nbr_data - 1 - address)) & 1
+    })
+}
+
+// two n-bit operands a (first n columns) and b; n + 1 output bits of a + b
+pub fn adder(nbr_bits: usize) -> BooleanSamples {
+    let mask = (1 << nbr_bits) - 1;
+    truth_table(2 * nbr_bits, nbr_bits + 1, |x| (x >> nbr_bits) + (x & mask))
+}
+
+// two n-bit operands a and b; 2n output bits of a * b
+pub fn multiplier(nbr_bits: usize) -> BooleanSamples {
+    let mask = (1 << nbr_bits) - 1;
+    truth_table(2 * nbr_bits, 2 * nbr_bits, |x| (x >> nbr_bits) * (x & mask))
+}
+
+// two n-bit operands a and b; outputs a < b, a == b, a > b
+pub fn comparator(nbr_bits: usize) -> BooleanSamples {
+    let mask = (1 << nbr_bits) - 1;
+    truth_table(2 * nbr_bits, 3, |x| {
+        let a = x >> nbr_bits;
+        let b = x & mask;
+        if a < b {
+            0b100
+        } else if a == b {
+            0b010
+        } else {
+            0b001
+        }
+    })
+}
+
+// true if more than half of the inputs are set
+pub fn majority(nbr_inputs: usize) -> BooleanSamples {
+    truth_table(nbr_inputs, 1, |x| if 2 * x.count_ones() as usize > nbr_inputs { 1 } else { 0 })
+}
+
+// "<problem>-<n>", e.g. parity-5, multiplexer-3 (address bits), adder-2,
+// multiplier-3, comparator-2 or majority-7; None if the problem has more than
+// MAX_INPUTS inputs
+pub fn find(spec: &str) -> Option<BooleanSamples> {
+    let (name, n) = spec.rsplit_once('-')?;
+    let n = n.parse::<usize>().ok().filter(|&n| n > 0)?;
+    let nbr_inputs = match name {
+        "parity" | "majority" => n,
+        "multiplexer" => n + 1usize.checked_shl(u32::try_from(n).ok()?)?,
+        "adder" | "multiplier" | "comparator" => n.checked_mul(2)?,
+        _ => return None,
+    };
+    if nbr_inputs > MAX_INPUTS {
+        return None;
+    }
+    match name {
+        "parity" => Some(even_parity(n)),
+        "multiplexer" => Some(multiplexer(n)),
+        "adder" => Some(adder(n)),
+        "multiplier" => Some(multiplier(n)),
+        "comparator" => Some(comparator(n)),
+        "majority" => Some(majority(n)),
+        _ => None,
+    }
+}
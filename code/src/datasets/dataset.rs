@@ -0,0 +1,177 @@
+use crate::datasets::arff;
+use crate::datasets::loader::{self, DatasetError, DatasetSchema, Samples, Target};
+use crate::datasets::scaling::ScalingMethod;
+use crate::datasets::sr_benchmarks::{Benchmark, BenchmarkSettings};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TaskKind {
+    #[default]
+    Regression,
+    Classification,
+}
+
+impl FromStr for TaskKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "regression" => Ok(TaskKind::Regression),
+            "classification" => Ok(TaskKind::Classification),
+            _ => Err(format!("unknown task '{}', expected regression or classification", s)),
+        }
+    }
+}
+
+impl Display for TaskKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskKind::Regression => write!(f, "regression"),
+            TaskKind::Classification => write!(f, "classification"),
+        }
+    }
+}
+
+pub trait Dataset {
+    fn name(&self) -> &'static str;
+    fn task(&self) -> TaskKind;
+    // may need to read the file header, hence the data root
+    fn feature_names(&self, data_root: &Path) -> Result<Vec<String>, DatasetError>;
+    // empty for regression datasets
+    fn class_names(&self, data_root: &Path) -> Result<Vec<String>, DatasetError>;
+    fn load(&self, data_root: &Path) -> Result<Samples, DatasetError>;
+
+    // (train, test) for datasets that come with a fixed partition, which is
+    // then used instead of a random split
+    fn load_partitioned(&self, _data_root: &Path) -> Option<Result<(Samples, Samples), DatasetError>> {
+        None
+    }
+
+    fn default_scaling(&self) -> ScalingMethod {
+        ScalingMethod::MinMax
+    }
+
+    fn default_target_scaling(&self) -> ScalingMethod {
+        match self.task() {
+            TaskKind::Regression => ScalingMethod::ZScore,
+            TaskKind::Classification => ScalingMethod::None,
+        }
+    }
+}
+
+// symbolic regression benchmark with its published train/test sampling
+pub struct BenchmarkDataset {
+    pub name: &'static str,
+    pub benchmark: Benchmark,
+    pub settings: BenchmarkSettings,
+}
+
+impl Dataset for BenchmarkDataset {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn task(&self) -> TaskKind {
+        TaskKind::Regression
+    }
+
+    fn feature_names(&self, _data_root: &Path) -> Result<Vec<String>, DatasetError> {
+        Ok((0..self.benchmark.nbr_inputs).map(|x| format!("x{}", x)).collect())
+    }
+
+    fn class_names(&self, _data_root: &Path) -> Result<Vec<String>, DatasetError> {
+        Ok(Vec::new())
+    }
+
+    // training samples followed by the test samples
+    fn load(&self, _data_root: &Path) -> Result<Samples, DatasetError> {
+        let ((mut data, mut labels), test) = self.benchmark.generate(&self.settings);
+        if let Some((test_data, test_labels)) = test {
+            data.extend(test_data);
+            labels.extend(test_labels);
+        }
+        Ok((data, labels))
+    }
+
+    // None for a grid without a test set, which is then split like any dataset
+    fn load_partitioned(&self, _data_root: &Path) -> Option<Result<(Samples, Samples), DatasetError>> {
+        let (train, test) = self.benchmark.generate(&self.settings);
+        test.map(|test| Ok((train, test)))
+    }
+
+    // the benchmarks are defined on their raw domain
+    fn default_scaling(&self) -> ScalingMethod {
+        ScalingMethod::None
+    }
+
+    fn default_target_scaling(&self) -> ScalingMethod {
+        ScalingMethod::None
+    }
+}
+
+// delimited text file described by a schema
+pub struct SchemaDataset {
+    pub name: &'static str,
+    pub schema: fn() -> DatasetSchema,
+}
+
+impl Dataset for SchemaDataset {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn task(&self) -> TaskKind {
+        match (self.schema)().target {
+            Target::Regression(_) | Target::MultiRegression(_) => TaskKind::Regression,
+            Target::Classification(_, _) => TaskKind::Classification,
+        }
+    }
+
+    fn feature_names(&self, data_root: &Path) -> Result<Vec<String>, DatasetError> {
+        loader::feature_names(&(self.schema)(), data_root)
+    }
+
+    fn class_names(&self, _data_root: &Path) -> Result<Vec<String>, DatasetError> {
+        match (self.schema)().target {
+            Target::Regression(_) | Target::MultiRegression(_) => Ok(Vec::new()),
+            Target::Classification(_, classes) => Ok(classes.iter().map(|x| x.to_string()).collect()),
+        }
+    }
+
+    fn load(&self, data_root: &Path) -> Result<Samples, DatasetError> {
+        loader::load(&(self.schema)(), data_root)
+    }
+}
+
+// ARFF file relative to the data root, the class is its default class attribute
+pub struct ArffDataset {
+    pub name: &'static str,
+    pub file_name: &'static str,
+}
+
+impl Dataset for ArffDataset {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn task(&self) -> TaskKind {
+        TaskKind::Classification
+    }
+
+    fn feature_names(&self, data_root: &Path) -> Result<Vec<String>, DatasetError> {
+        let file = arff::parse(&data_root.join(self.file_name))?;
+        let indices = file.feature_indices(file.default_class_index());
+        Ok(indices.into_iter().map(|x| file.attributes[x].name.clone()).collect())
+    }
+
+    fn class_names(&self, data_root: &Path) -> Result<Vec<String>, DatasetError> {
+        let file = arff::parse(&data_root.join(self.file_name))?;
+        Ok(file.class_names(file.default_class_index()).unwrap_or_default().to_vec())
+    }
+
+    fn load(&self, data_root: &Path) -> Result<Samples, DatasetError> {
+        arff::load_from_root(data_root, self.file_name)
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImputationStrategy {
+    Mean,
+    Median,
+    // most frequent value, the smallest one on ties
+    Mode,
+    Constant(f32),
+    // mean imputation plus a 0/1 column per feature that had missing values
+    MissingIndicator,
+}
+
+impl FromStr for ImputationStrategy {
+    type Err = String;
+
+    // mean, median, mode, constant[:<value>] or indicator
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match s.split_once(':') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s, None),
+        };
+        match (name, value) {
+            ("mean", None) => Ok(ImputationStrategy::Mean),
+            ("median", None) => Ok(ImputationStrategy::Median),
+            ("mode", None) => Ok(ImputationStrategy::Mode),
+            ("constant", None) => Ok(ImputationStrategy::Constant(0.)),
+            ("constant", Some(value)) => value
+                .parse::<f32>()
+                .map(ImputationStrategy::Constant)
+                .map_err(|_| format!("invalid imputation constant '{}'", value)),
+            ("indicator", None) => Ok(ImputationStrategy::MissingIndicator),
+            _ => Err(format!(
+                "unknown imputation strategy '{}', expected mean, median, mode, constant[:<value>] or indicator",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for ImputationStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImputationStrategy::Mean => write!(f, "mean"),
+            ImputationStrategy::Median => write!(f, "median"),
+            ImputationStrategy::Mode => write!(f, "mode"),
+            ImputationStrategy::Constant(value) => write!(f, "constant:{}", value),
+            ImputationStrategy::MissingIndicator => write!(f, "indicator"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Imputer {
+    #[serde(with = "crate::utils::serde_str")]
+    pub strategy: ImputationStrategy,
+    // one per input column
+    pub fill_values: Vec<f32>,
+    // columns that get an indicator column appended, in order
+    pub indicator_columns: Vec<usize>,
+}
+
+impl Imputer {
+    // missing values are NaN; a column without any observed value is filled with 0
+    pub fn fit(strategy: ImputationStrategy, data: &[Vec<f32>]) -> Imputer {
+        let nbr_columns = data[0].len();
+        let mut fill_values = Vec::with_capacity(nbr_columns);
+        let mut indicator_columns = Vec::new();
+        for j in 0..nbr_columns {
+            let mut observed: Vec<f32> = data.iter().map(|row| row[j]).filter(|x| !x.is_nan()).collect();
+            if observed.len() < data.len() && strategy == ImputationStrategy::MissingIndicator {
+                indicator_columns.push(j);
+            }
+            if observed.is_empty() {
+                fill_values.push(0.);
+                continue;
+            }
+            let fill_value = match strategy {
+                ImputationStrategy::Mean | ImputationStrategy::MissingIndicator => {
+                    observed.iter().sum::<f32>() / observed.len() as f32
+                }
+                ImputationStrategy::Median => {
+                    observed.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                    let middle = observed.len() / 2;
+                    if observed.len().is_multiple_of(2) {
+                        (observed[middle - 1] + observed[middle]) / 2.
+                    } else {
+                        observed[middle]
+                    }
+                }
+                ImputationStrategy::Mode => mode(&observed),
+                ImputationStrategy::Constant(value) => value,
+            };
+            fill_values.push(fill_value);
+        }
+        Imputer { strategy, fill_values, indicator_columns }
+    }
+
+    // returns the number of imputed values per input column
+    pub fn transform(&self, data: &mut [Vec<f32>]) -> Vec<usize> {
+        let mut imputed = vec![0; self.fill_values.len()];
+        for row in data.iter_mut() {
+            let mut indicators = Vec::with_capacity(self.indicator_columns.len());
+            for &j in &self.indicator_columns {
+                indicators.push(if row[j].is_nan() { 1. } else { 0. });
+            }
+            for j in 0..self.fill_values.len() {
+                if row[j].is_nan() {
+                    row[j] = self.fill_values[j];
+                    imputed[j] += 1;
+                }
+            }
+            row.extend(indicators);
+        }
+        imputed
+    }
+}
+
+fn mode(values: &[f32]) -> f32 {
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value.to_bits()).or_insert(0) += 1;
+    }
+    let mut best = values[0];
+    let mut best_count = 0;
+    for (bits, count) in counts {
+        let value = f32::from_bits(bits);
+        if count > best_count || (count == best_count && value < best) {
+            best = value;
+            best_count = count;
+        }
+    }
+    best
+}
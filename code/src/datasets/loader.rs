@@ -0,0 +1,263 @@
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+pub const DATA_ROOT_ENV: &str = "CGP_DATA_ROOT";
+pub const DEFAULT_DATA_ROOT: &str = "src/datasets/Data";
+// filled in after loading, see imputation::Imputer
+pub const MISSING_VALUE: f32 = f32::NAN;
+
+// (inputs, labels), one row per sample
+pub type Samples = (Vec<Vec<f32>>, Vec<Vec<f32>>);
+
+#[derive(Debug)]
+pub enum DatasetError {
+    Io { path: PathBuf, source: io::Error },
+    MissingColumn { path: PathBuf, line: usize, column: usize },
+    InvalidValue { path: PathBuf, line: usize, column: usize, value: String },
+    UnknownClass { path: PathBuf, line: usize, value: String },
+    Empty { path: PathBuf },
+    Malformed { path: PathBuf, line: usize, message: String },
+    // no attribute of that name
+    UnknownAttribute { path: PathBuf, name: String },
+    // the class attribute is not nominal
+    ClassAttribute { path: PathBuf, name: String },
+}
+
+impl Display for DatasetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatasetError::Io { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            DatasetError::MissingColumn { path, line, column } => {
+                write!(f, "{}:{}: column {} is missing", path.display(), line, column)
+            }
+            DatasetError::InvalidValue { path, line, column, value } => {
+                write!(f, "{}:{}: invalid value '{}' in column {}", path.display(), line, value, column)
+            }
+            DatasetError::UnknownClass { path, line, value } => {
+                write!(f, "{}:{}: unknown class '{}'", path.display(), line, value)
+            }
+            DatasetError::Empty { path } => write!(f, "{} contains no records", path.display()),
+            DatasetError::Malformed { path, line, message } => {
+                write!(f, "{}:{}: {}", path.display(), line, message)
+            }
+            DatasetError::UnknownAttribute { path, name } => {
+                write!(f, "{}: no attribute called '{}'", path.display(), name)
+            }
+            DatasetError::ClassAttribute { path, name } => {
+                write!(f, "{}: '{}' is not a nominal attribute", path.display(), name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DatasetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DatasetError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub enum Encoding {
+    Numeric,
+    // value is the position in the list
+    Ordinal(&'static [&'static str]),
+    Mapped(&'static [(&'static str, f32)]),
+    Derived(fn(&str) -> Option<f32>),
+}
+
+impl Encoding {
+    fn encode(&self, value: &str, missing_token: Option<&str>) -> Option<f32> {
+        match self {
+            Encoding::Numeric => {
+                if missing_token == Some(value) {
+                    Some(MISSING_VALUE)
+                } else {
+                    value.parse::<f32>().ok()
+                }
+            }
+            Encoding::Ordinal(values) => Some(
+                values
+                    .iter()
+                    .position(|x| *x == value)
+                    .map_or(MISSING_VALUE, |x| x as f32),
+            ),
+            Encoding::Mapped(values) => Some(
+                values
+                    .iter()
+                    .find(|(name, _)| *name == value)
+                    .map_or(MISSING_VALUE, |(_, x)| *x),
+            ),
+            Encoding::Derived(function) => function(value),
+        }
+    }
+}
+
+pub struct FeatureColumn {
+    pub column: usize,
+    pub encoding: Encoding,
+}
+
+pub enum Target {
+    Regression(usize),
+    // several targets predicted at once, one label column each
+    MultiRegression(&'static [usize]),
+    // one-hot encoded in the order of the class list
+    Classification(usize, &'static [&'static str]),
+}
+
+pub struct DatasetSchema {
+    // relative to the data root
+    pub file_name: &'static str,
+    pub delimiter: char,
+    pub header_rows: usize,
+    // names for files without a header row, otherwise taken from the first line
+    pub column_names: &'static [&'static str],
+    pub missing_token: Option<&'static str>,
+    pub strip_quotes: bool,
+    pub features: Vec<FeatureColumn>,
+    pub target: Target,
+}
+
+pub fn numeric(columns: Range<usize>) -> Vec<FeatureColumn> {
+    columns
+        .map(|column| FeatureColumn { column, encoding: Encoding::Numeric })
+        .collect()
+}
+
+pub fn column(column: usize, encoding: Encoding) -> FeatureColumn {
+    FeatureColumn { column, encoding }
+}
+
+/// Data root from the command line, the `CGP_DATA_ROOT` environment variable
+/// or `src/datasets/Data`, in that order.
+pub fn resolve_data_root(cli_root: Option<PathBuf>) -> PathBuf {
+    if let Some(root) = cli_root {
+        return root;
+    }
+    match env::var_os(DATA_ROOT_ENV) {
+        Some(root) => PathBuf::from(root),
+        None => PathBuf::from(DEFAULT_DATA_ROOT),
+    }
+}
+
+pub fn load(schema: &DatasetSchema, data_root: &Path) -> Result<Samples, DatasetError> {
+    let path = data_root.join(schema.file_name);
+    let file = File::open(&path).map_err(|source| DatasetError::Io { path: path.clone(), source })?;
+    let reader = io::BufReader::new(file);
+
+    let mut data: Vec<Vec<f32>> = Vec::new();
+    let mut labels: Vec<Vec<f32>> = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line_nbr = i + 1;
+        let record = line.map_err(|source| DatasetError::Io { path: path.clone(), source })?;
+        if i < schema.header_rows || record.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = record
+            .split(schema.delimiter)
+            .map(|x| clean_field(x, schema.strip_quotes))
+            .collect();
+
+        let mut row = Vec::with_capacity(schema.features.len());
+        for feature in &schema.features {
+            let value = get_field(&fields, feature.column, &path, line_nbr)?;
+            let encoded = feature
+                .encoding
+                .encode(value, schema.missing_token)
+                .ok_or_else(|| DatasetError::InvalidValue {
+                    path: path.clone(),
+                    line: line_nbr,
+                    column: feature.column,
+                    value: value.to_string(),
+                })?;
+            row.push(encoded);
+        }
+        data.push(row);
+
+        match schema.target {
+            Target::Regression(target_column) => {
+                labels.push(vec![regression_target(&fields, target_column, &path, line_nbr)?]);
+            }
+            Target::MultiRegression(target_columns) => {
+                let mut targets = Vec::with_capacity(target_columns.len());
+                for &target_column in target_columns {
+                    targets.push(regression_target(&fields, target_column, &path, line_nbr)?);
+                }
+                labels.push(targets);
+            }
+            Target::Classification(target_column, classes) => {
+                let value = get_field(&fields, target_column, &path, line_nbr)?;
+                let class = classes.iter().position(|x| *x == value).ok_or_else(|| {
+                    DatasetError::UnknownClass { path: path.clone(), line: line_nbr, value: value.to_string() }
+                })?;
+                let mut one_hot = vec![0.; classes.len()];
+                one_hot[class] = 1.;
+                labels.push(one_hot);
+            }
+        }
+    }
+
+    if data.is_empty() {
+        return Err(DatasetError::Empty { path });
+    }
+    Ok((data, labels))
+}
+
+pub fn feature_names(schema: &DatasetSchema, data_root: &Path) -> Result<Vec<String>, DatasetError> {
+    let names = column_names(schema, data_root)?;
+    Ok(schema.features.iter().map(|x| name_of(&names, x.column)).collect())
+}
+
+fn column_names(schema: &DatasetSchema, data_root: &Path) -> Result<Vec<String>, DatasetError> {
+    if !schema.column_names.is_empty() || schema.header_rows == 0 {
+        return Ok(schema.column_names.iter().map(|x| x.to_string()).collect());
+    }
+    let path = data_root.join(schema.file_name);
+    let file = File::open(&path).map_err(|source| DatasetError::Io { path: path.clone(), source })?;
+    let mut header = String::new();
+    io::BufReader::new(file)
+        .read_line(&mut header)
+        .map_err(|source| DatasetError::Io { path: path.clone(), source })?;
+    Ok(header.split(schema.delimiter).map(|x| clean_field(x, true).to_string()).collect())
+}
+
+// unnamed columns are called x<column>
+fn name_of(names: &[String], column: usize) -> String {
+    match names.get(column) {
+        Some(name) if !name.is_empty() => name.clone(),
+        _ => format!("x{}", column),
+    }
+}
+
+fn clean_field(field: &str, strip_quotes: bool) -> &str {
+    let field = field.trim();
+    if strip_quotes && field.len() >= 2 && field.starts_with('"') && field.ends_with('"') {
+        return field[1..field.len() - 1].trim();
+    }
+    field
+}
+
+fn get_field<'a>(fields: &[&'a str], column: usize, path: &Path, line: usize) -> Result<&'a str, DatasetError> {
+    fields
+        .get(column)
+        .copied()
+        .ok_or_else(|| DatasetError::MissingColumn { path: path.to_path_buf(), line, column })
+}
+
+fn regression_target(fields: &[&str], column: usize, path: &Path, line: usize) -> Result<f32, DatasetError> {
+    let value = get_field(fields, column, path, line)?;
+    value.parse::<f32>().map_err(|_| DatasetError::InvalidValue {
+        path: path.to_path_buf(),
+        line,
+        column,
+        value: value.to_string(),
+    })
+}
@@ -1,5 +1,14 @@
-pub mod koza;
+pub mod loader;
 pub mod adult;
+pub mod arff;
+pub mod boolean;
+pub mod dataset;
+pub mod imputation;
+pub mod pla;
+pub mod preprocessing;
+pub mod scaling;
+pub mod split;
+pub mod sr_benchmarks;
 pub mod bach;
 pub mod car;
 pub mod chronic_kidney_disease;
@@ -19,5 +28,58 @@ pub mod energydata;
 pub mod forestfires;
 pub mod winequality_red;
 pub mod winequality_white;
-pub mod nguyen;
-pub mod pagie;
+
+use crate::datasets::dataset::{ArffDataset, BenchmarkDataset, Dataset, SchemaDataset};
+use crate::datasets::sr_benchmarks::BenchmarkSettings;
+
+// the position in this list is the dataset id used in result directories;
+// koza, pagie and nguyen keep their old ids and stand for Koza-3, Pagie-1 and Nguyen-7
+pub fn registry(settings: &BenchmarkSettings) -> Vec<Box<dyn Dataset>> {
+    let benchmark = |name: &'static str, benchmark: &str| -> Box<dyn Dataset> {
+        Box::new(BenchmarkDataset {
+            name,
+            benchmark: sr_benchmarks::find(benchmark).expect("unknown benchmark"),
+            settings: settings.clone(),
+        })
+    };
+    let mut datasets: Vec<Box<dyn Dataset>> = vec![
+        benchmark("koza", "koza-3"),
+        benchmark("pagie", "pagie-1"),
+        benchmark("nguyen", "nguyen-7"),
+        Box::new(SchemaDataset { name: "pollution", schema: pollution::schema }),
+        Box::new(SchemaDataset { name: "analcatdata", schema: analcatdata::schema }),
+        Box::new(SchemaDataset { name: "abalone", schema: abalone::schema }),
+        Box::new(SchemaDataset { name: "bike_sharing_day", schema: bike_sharing_day::schema }),
+        Box::new(SchemaDataset { name: "bike_sharing_hour", schema: bike_sharing_hour::schema }),
+        Box::new(SchemaDataset { name: "cal_housing", schema: cal_housing::schema }),
+        Box::new(SchemaDataset { name: "diabetes", schema: diabetes::schema }),
+        Box::new(SchemaDataset { name: "energydata", schema: energydata::schema }),
+        Box::new(SchemaDataset { name: "forestfires", schema: forestfires::schema }),
+        Box::new(SchemaDataset { name: "winequality_white", schema: winequality_white::schema }),
+        Box::new(SchemaDataset { name: "winequality_red", schema: winequality_red::schema }),
+        Box::new(SchemaDataset { name: "adult", schema: adult::schema }),
+        Box::new(ArffDataset { name: "bach", file_name: bach::FILE_NAME }),
+        Box::new(SchemaDataset { name: "car", schema: car::schema }),
+        Box::new(ArffDataset { name: "chronic_kidney_disease", file_name: chronic_kidney_disease::FILE_NAME }),
+        Box::new(ArffDataset { name: "forest", file_name: forest::FILE_NAME }),
+        Box::new(ArffDataset { name: "human", file_name: human::FILE_NAME }),
+        Box::new(ArffDataset { name: "iris", file_name: iris::FILE_NAME }),
+        Box::new(ArffDataset { name: "wall24", file_name: wall24::FILE_NAME }),
+        Box::new(ArffDataset { name: "wine_quality", file_name: wine_quality::FILE_NAME }),
+    ];
+    for x in sr_benchmarks::benchmarks() {
+        datasets.push(Box::new(BenchmarkDataset { name: x.name, benchmark: x, settings: settings.clone() }));
+    }
+    datasets
+}
+
+// looks a dataset up by name or by its id in the registry
+pub fn find(key: &str, settings: &BenchmarkSettings) -> Option<(usize, Box<dyn Dataset>)> {
+    let datasets = registry(settings);
+    let id = match key.parse::<usize>() {
+        Ok(id) if id < datasets.len() => id,
+        Ok(_) => return None,
+        Err(_) => datasets.iter().position(|x| x.name() == key)?,
+    };
+    datasets.into_iter().nth(id).map(|x| (id, x))
+}
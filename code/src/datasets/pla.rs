@@ -0,0 +1,274 @@
+use crate::datasets::loader::DatasetError;
+use ndarray::prelude::*;
+use std::fs;
+use std::path::Path;
+
+// the full truth table has 2^inputs rows
+const MAX_INPUTS: usize = 20;
+
+// mask is false where the output is a don't care, see fitness_boolean_masked
+pub struct MaskedBooleanSamples {
+    pub inputs: Array2<bool>,
+    pub labels: Array2<bool>,
+    pub mask: Array2<bool>,
+    pub input_names: Vec<String>,
+    pub output_names: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    Off,
+    On,
+    DontCare,
+}
+
+// espresso .type, it decides what unlisted minterms and '0' outputs mean
+#[derive(Clone, Copy, PartialEq)]
+enum PlaType {
+    F,
+    Fd,
+    Fr,
+    Fdr,
+}
+
+// espresso-style .pla file; the result is the complete truth table with the
+// rows in the order of boolean::truth_table (most significant input first)
+pub fn load_pla(path: &Path) -> Result<MaskedBooleanSamples, DatasetError> {
+    let content = fs::read_to_string(path).map_err(|source| DatasetError::Io { path: path.to_path_buf(), source })?;
+
+    let mut nbr_inputs = None;
+    let mut nbr_outputs = None;
+    let mut pla_type = PlaType::Fd;
+    let mut input_names = Vec::new();
+    let mut output_names = Vec::new();
+    let mut cubes: Vec<(usize, String)> = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line_nbr = i + 1;
+        let malformed = |message: String| DatasetError::Malformed { path: path.to_path_buf(), line: line_nbr, message };
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !line.starts_with('.') {
+            cubes.push((line_nbr, line.chars().filter(|x| !x.is_whitespace() && *x != '|').collect()));
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+        let directive = tokens.next().unwrap();
+        let arguments: Vec<&str> = tokens.collect();
+        let number = || -> Result<usize, DatasetError> {
+            arguments
+                .first()
+                .and_then(|x| x.parse::<usize>().ok())
+                .ok_or_else(|| malformed(format!("{} needs a number", directive)))
+        };
+        match directive {
+            ".i" => nbr_inputs = Some(number()?),
+            ".o" => nbr_outputs = Some(number()?),
+            // the product count is only a hint
+            ".p" => {
+                number()?;
+            }
+            ".ilb" => input_names = arguments.iter().map(|x| x.to_string()).collect(),
+            ".ob" => output_names = arguments.iter().map(|x| x.to_string()).collect(),
+            ".type" => {
+                pla_type = match arguments.first().copied() {
+                    Some("f") => PlaType::F,
+                    Some("fd") => PlaType::Fd,
+                    Some("fr") => PlaType::Fr,
+                    Some("fdr") => PlaType::Fdr,
+                    other => return Err(malformed(format!("unsupported .type {:?}", other))),
+                }
+            }
+            ".e" | ".end" => break,
+            _ => return Err(malformed(format!("unsupported directive {}", directive))),
+        }
+    }
+
+    let missing = |directive: &str| DatasetError::Malformed {
+        path: path.to_path_buf(),
+        line: 0,
+        message: format!("missing {}", directive),
+    };
+    let nbr_inputs = nbr_inputs.ok_or_else(|| missing(".i"))?;
+    let nbr_outputs = nbr_outputs.ok_or_else(|| missing(".o"))?;
+    if nbr_inputs > MAX_INPUTS {
+        return Err(DatasetError::Malformed {
+            path: path.to_path_buf(),
+            line: 0,
+            message: format!("{} inputs, at most {} are supported", nbr_inputs, MAX_INPUTS),
+        });
+    }
+    if cubes.is_empty() {
+        return Err(DatasetError::Empty { path: path.to_path_buf() });
+    }
+
+    // unlisted minterms are off for f/fd and don't cares for fr/fdr
+    let initial = match pla_type {
+        PlaType::F | PlaType::Fd => Phase::Off,
+        PlaType::Fr | PlaType::Fdr => Phase::DontCare,
+    };
+    let nbr_rows = 1 << nbr_inputs;
+    let mut phases = Array2::from_elem((nbr_rows, nbr_outputs), initial);
+    for (line_nbr, cube) in &cubes {
+        if cube.len() != nbr_inputs + nbr_outputs {
+            return Err(DatasetError::Malformed {
+                path: path.to_path_buf(),
+                line: *line_nbr,
+                message: format!("expected {} input and {} output values", nbr_inputs, nbr_outputs),
+            });
+        }
+        let (input_cube, output_cube) = cube.split_at(nbr_inputs);
+        let rows = expand_cube(input_cube).ok_or_else(|| DatasetError::InvalidValue {
+            path: path.to_path_buf(),
+            line: *line_nbr,
+            column: 0,
+            value: input_cube.to_string(),
+        })?;
+        for (column, value) in output_cube.chars().enumerate() {
+            for &row in &rows {
+                let phase = &mut phases[[row, column]];
+                match value {
+                    '1' => *phase = Phase::On,
+                    '0' if matches!(pla_type, PlaType::Fr | PlaType::Fdr) && *phase != Phase::On => {
+                        *phase = Phase::Off
+                    }
+                    '-' | '2' if matches!(pla_type, PlaType::Fd | PlaType::Fdr) && *phase != Phase::On => {
+                        *phase = Phase::DontCare
+                    }
+                    // '~' has no meaning
+                    '0' | '-' | '~' | '2' => {}
+                    _ => {
+                        return Err(DatasetError::InvalidValue {
+                            path: path.to_path_buf(),
+                            line: *line_nbr,
+                            column: nbr_inputs + column,
+                            value: value.to_string(),
+                        })
+                    }
+                }
+            }
+        }
+    }
+
+    let inputs = Array2::from_shape_fn((nbr_rows, nbr_inputs), |(row, column)| {
+        (row >> (nbr_inputs - 1 - column)) & 1 == 1
+    });
+    Ok(MaskedBooleanSamples {
+        inputs,
+        labels: phases.mapv(|x| x == Phase::On),
+        mask: phases.mapv(|x| x != Phase::DontCare),
+        input_names: names_or_default(input_names, nbr_inputs, "x"),
+        output_names: names_or_default(output_names, nbr_outputs, "y"),
+    })
+}
+
+// comma separated rows of 0/1 values, the last nbr_outputs columns are the
+// outputs; '-' in an input expands to both values, '-' or 'x' in an output is
+// a don't care. An optional first line with column names is detected
+// automatically. Rows are kept in file order.
+pub fn load_truth_table(path: &Path, nbr_outputs: usize) -> Result<MaskedBooleanSamples, DatasetError> {
+    let content = fs::read_to_string(path).map_err(|source| DatasetError::Io { path: path.to_path_buf(), source })?;
+
+    let mut names: Vec<String> = Vec::new();
+    let mut input_rows: Vec<Vec<bool>> = Vec::new();
+    let mut label_rows: Vec<Vec<bool>> = Vec::new();
+    let mut mask_rows: Vec<Vec<bool>> = Vec::new();
+    let mut nbr_columns = None;
+    for (i, line) in content.lines().enumerate() {
+        let line_nbr = i + 1;
+        let fields: Vec<&str> = line.split(',').map(|x| x.trim()).collect();
+        if line.trim().is_empty() {
+            continue;
+        }
+        if input_rows.is_empty() && names.is_empty() && fields.iter().any(|x| !matches!(*x, "0" | "1" | "-" | "x")) {
+            names = fields.iter().map(|x| x.to_string()).collect();
+            nbr_columns = Some(fields.len());
+            continue;
+        }
+        if *nbr_columns.get_or_insert(fields.len()) != fields.len() || fields.len() <= nbr_outputs {
+            return Err(DatasetError::Malformed {
+                path: path.to_path_buf(),
+                line: line_nbr,
+                message: format!("expected {} columns with {} outputs", nbr_columns.unwrap(), nbr_outputs),
+            });
+        }
+        let (input_fields, output_fields) = fields.split_at(fields.len() - nbr_outputs);
+        let mut labels = Vec::with_capacity(nbr_outputs);
+        let mut mask = Vec::with_capacity(nbr_outputs);
+        for (column, value) in output_fields.iter().enumerate() {
+            match *value {
+                "0" | "1" => {
+                    labels.push(*value == "1");
+                    mask.push(true);
+                }
+                "-" | "x" => {
+                    labels.push(false);
+                    mask.push(false);
+                }
+                _ => {
+                    return Err(DatasetError::InvalidValue {
+                        path: path.to_path_buf(),
+                        line: line_nbr,
+                        column: input_fields.len() + column,
+                        value: value.to_string(),
+                    })
+                }
+            }
+        }
+        let cube: String = input_fields.concat();
+        let rows = expand_cube(&cube).filter(|_| cube.len() == input_fields.len()).ok_or_else(|| {
+            DatasetError::InvalidValue { path: path.to_path_buf(), line: line_nbr, column: 0, value: line.to_string() }
+        })?;
+        let nbr_inputs = input_fields.len();
+        for row in rows {
+            input_rows.push((0..nbr_inputs).map(|j| (row >> (nbr_inputs - 1 - j)) & 1 == 1).collect());
+            label_rows.push(labels.clone());
+            mask_rows.push(mask.clone());
+        }
+    }
+
+    if input_rows.is_empty() {
+        return Err(DatasetError::Empty { path: path.to_path_buf() });
+    }
+    let nbr_inputs = input_rows[0].len();
+    let (input_names, output_names) = if names.is_empty() {
+        (Vec::new(), Vec::new())
+    } else {
+        let output_names = names.split_off(nbr_inputs);
+        (names, output_names)
+    };
+    Ok(MaskedBooleanSamples {
+        inputs: to_array(&input_rows),
+        labels: to_array(&label_rows),
+        mask: to_array(&mask_rows),
+        input_names: names_or_default(input_names, nbr_inputs, "x"),
+        output_names: names_or_default(output_names, nbr_outputs, "y"),
+    })
+}
+
+// truth table rows (most significant input first) covered by a cube of 0, 1 and -
+fn expand_cube(cube: &str) -> Option<Vec<usize>> {
+    let mut rows = vec![0];
+    for value in cube.chars() {
+        rows = match value {
+            '0' => rows.iter().map(|x| x << 1).collect(),
+            '1' => rows.iter().map(|x| (x << 1) | 1).collect(),
+            '-' => rows.iter().flat_map(|x| [x << 1, (x << 1) | 1]).collect(),
+            _ => return None,
+        };
+    }
+    Some(rows)
+}
+
+fn to_array(rows: &[Vec<bool>]) -> Array2<bool> {
+    Array2::from_shape_fn((rows.len(), rows[0].len()), |(i, j)| rows[i][j])
+}
+
+fn names_or_default(names: Vec<String>, nbr_names: usize, prefix: &str) -> Vec<String> {
+    if names.len() == nbr_names {
+        return names;
+    }
+    (0..nbr_names).map(|x| format!("{}{}", prefix, x)).collect()
+}
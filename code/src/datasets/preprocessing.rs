@@ -0,0 +1,63 @@
+use crate::datasets::imputation::{ImputationStrategy, Imputer};
+use crate::datasets::scaling::{Scaler, ScalingMethod};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Clone, Debug)]
+pub struct Preprocessing {
+    pub imputation: ImputationStrategy,
+    pub scaling: ScalingMethod,
+    // only applied to regression targets, one-hot labels are left alone
+    pub target_scaling: ScalingMethod,
+}
+
+// everything is fitted on the training partition and then applied unchanged
+// to the test partition and to any new raw data
+#[derive(Serialize, Deserialize)]
+pub struct Preprocessor {
+    pub imputer: Imputer,
+    pub scaler: Scaler,
+    pub target_scaler: Scaler,
+}
+
+impl Preprocessor {
+    pub fn fit(config: &Preprocessing, data: &[Vec<f32>], labels: &[Vec<f32>]) -> Preprocessor {
+        // indicator columns only for the features with missing training values
+        let imputer = Imputer::fit(config.imputation.clone(), data);
+
+        let mut imputed = data.to_vec();
+        imputer.transform(&mut imputed);
+        // indicator columns are already 0/1 and stay unscaled
+        let scaler = Scaler::fit(config.scaling, &without_indicators(&imputed, imputer.fill_values.len()));
+        let target_scaler = Scaler::fit(config.target_scaling, labels);
+        Preprocessor { imputer, scaler, target_scaler }
+    }
+
+    // returns the number of imputed values per input column
+    pub fn transform(&self, data: &mut [Vec<f32>], labels: &mut [Vec<f32>]) -> Vec<usize> {
+        let imputed_counts = self.imputer.transform(data);
+        self.scaler.transform(data);
+        self.target_scaler.transform(labels);
+        imputed_counts
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        writeln!(file, "imputation: {}", self.imputer.strategy)?;
+        for (j, value) in self.imputer.fill_values.iter().enumerate() {
+            writeln!(file, "{} {}", j, value)?;
+        }
+        writeln!(file, "indicator_columns: {:?}", self.imputer.indicator_columns)?;
+        writeln!(file, "[inputs]")?;
+        write!(file, "{}", self.scaler)?;
+        writeln!(file, "[targets]")?;
+        write!(file, "{}", self.target_scaler)?;
+        Ok(())
+    }
+}
+
+fn without_indicators(data: &[Vec<f32>], nbr_columns: usize) -> Vec<Vec<f32>> {
+    data.iter().map(|row| row[..nbr_columns].to_vec()).collect()
+}
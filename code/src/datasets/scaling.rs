@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScalingMethod {
+    // to [0, 1]
+    MinMax,
+    // zero mean, unit variance
+    ZScore,
+    // zero median, unit interquartile range
+    Robust,
+    None,
+}
+
+impl FromStr for ScalingMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minmax" => Ok(ScalingMethod::MinMax),
+            "zscore" => Ok(ScalingMethod::ZScore),
+            "robust" => Ok(ScalingMethod::Robust),
+            "none" => Ok(ScalingMethod::None),
+            _ => Err(format!("unknown scaling '{}', expected minmax, zscore, robust or none", s)),
+        }
+    }
+}
+
+impl Display for ScalingMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScalingMethod::MinMax => write!(f, "minmax"),
+            ScalingMethod::ZScore => write!(f, "zscore"),
+            ScalingMethod::Robust => write!(f, "robust"),
+            ScalingMethod::None => write!(f, "none"),
+        }
+    }
+}
+
+// x' = (x - offset) / scale, column-wise
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scaler {
+    #[serde(with = "crate::utils::serde_str")]
+    pub method: ScalingMethod,
+    pub offsets: Vec<f32>,
+    pub scales: Vec<f32>,
+}
+
+impl Scaler {
+    // NaN values are ignored; constant columns get a scale of 1
+    pub fn fit(method: ScalingMethod, data: &[Vec<f32>]) -> Scaler {
+        let nbr_columns = data[0].len();
+        let mut offsets = vec![0.; nbr_columns];
+        let mut scales = vec![1.; nbr_columns];
+        for j in 0..nbr_columns {
+            let mut values: Vec<f32> = data.iter().map(|row| row[j]).filter(|x| !x.is_nan()).collect();
+            if values.is_empty() {
+                continue;
+            }
+            let (offset, scale) = match method {
+                ScalingMethod::MinMax => {
+                    let min = values.iter().cloned().fold(f32::INFINITY, f32::min);
+                    let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+                    (min, max - min)
+                }
+                ScalingMethod::ZScore => {
+                    let n = values.len() as f32;
+                    let mean = values.iter().sum::<f32>() / n;
+                    let deviation = (values.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / n).sqrt();
+                    (mean, deviation)
+                }
+                ScalingMethod::Robust => {
+                    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                    let median = quantile(&values, 0.5);
+                    (median, quantile(&values, 0.75) - quantile(&values, 0.25))
+                }
+                ScalingMethod::None => (0., 1.),
+            };
+            offsets[j] = offset;
+            scales[j] = if scale > 0. { scale } else { 1. };
+        }
+        Scaler { method, offsets, scales }
+    }
+
+    pub fn transform(&self, data: &mut [Vec<f32>]) {
+        for row in data.iter_mut() {
+            for (j, value) in row.iter_mut().take(self.offsets.len()).enumerate() {
+                *value = (*value - self.offsets[j]) / self.scales[j];
+            }
+        }
+    }
+
+    pub fn inverse_transform(&self, data: &mut [Vec<f32>]) {
+        for row in data.iter_mut() {
+            for (j, value) in row.iter_mut().take(self.offsets.len()).enumerate() {
+                *value = *value * self.scales[j] + self.offsets[j];
+            }
+        }
+    }
+}
+
+impl Display for Scaler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "method: {}", self.method)?;
+        for j in 0..self.offsets.len() {
+            writeln!(f, "{} {} {}", j, self.offsets[j], self.scales[j])?;
+        }
+        Ok(())
+    }
+}
+
+// linear interpolation between the closest ranks of sorted values
+fn quantile(sorted: &[f32], q: f32) -> f32 {
+    let position = q * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f32)
+}
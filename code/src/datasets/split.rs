@@ -0,0 +1,136 @@
+use crate::datasets::dataset::TaskKind;
+use crate::utils::random::{self, CgpRng};
+use rand::seq::SliceRandom;
+
+#[derive(Clone, Debug)]
+pub enum SplitStrategy {
+    // independent random train/test splits
+    Holdout { test_ratio: f32, repetitions: usize },
+    // every sample is tested once per repeat
+    KFold { folds: usize, repeats: usize },
+    // the first train_size rows are the training set, the rest is the test set
+    Fixed { train_size: usize, repetitions: usize },
+}
+
+#[derive(Clone, Debug)]
+pub struct SplitConfig {
+    pub strategy: SplitStrategy,
+    // taken from the training part of each split, 0 disables it
+    pub validation_ratio: f32,
+    // keeps the class proportions of one-hot labels in every partition
+    pub stratify: bool,
+    pub seed: u64,
+}
+
+// row indices into the full dataset
+#[derive(Clone, Debug)]
+pub struct Split {
+    pub train: Vec<usize>,
+    pub validation: Vec<usize>,
+    pub test: Vec<usize>,
+}
+
+pub fn make_splits(config: &SplitConfig, labels: &[Vec<f32>]) -> Vec<Split> {
+    let mut rng = random::split_rng(config.seed);
+    let all: Vec<usize> = (0..labels.len()).collect();
+    let mut splits = Vec::new();
+    match config.strategy {
+        SplitStrategy::Holdout { test_ratio, repetitions } => {
+            for _ in 0..repetitions {
+                let (train, test) = partition(&all, labels, test_ratio, config.stratify, &mut rng);
+                splits.push(Split { train, validation: Vec::new(), test });
+            }
+        }
+        SplitStrategy::KFold { folds, repeats } => {
+            for _ in 0..repeats {
+                let mut fold_of = vec![0; labels.len()];
+                // dealing the shuffled strata out in turn keeps every fold stratified
+                let mut position = 0;
+                for mut stratum in strata(&all, labels, config.stratify) {
+                    stratum.shuffle(&mut rng);
+                    for index in stratum {
+                        fold_of[index] = position % folds;
+                        position += 1;
+                    }
+                }
+                for fold in 0..folds {
+                    let train = all.iter().cloned().filter(|&x| fold_of[x] != fold).collect();
+                    let test = all.iter().cloned().filter(|&x| fold_of[x] == fold).collect();
+                    splits.push(Split { train, validation: Vec::new(), test });
+                }
+            }
+        }
+        SplitStrategy::Fixed { train_size, repetitions } => {
+            for _ in 0..repetitions {
+                let train = (0..train_size).collect();
+                let test = (train_size..labels.len()).collect();
+                splits.push(Split { train, validation: Vec::new(), test });
+            }
+        }
+    }
+
+    if config.validation_ratio > 0. {
+        for split in splits.iter_mut() {
+            let (train, validation) = partition(&split.train, labels, config.validation_ratio, config.stratify, &mut rng);
+            split.train = train;
+            split.validation = validation;
+        }
+    }
+    splits
+}
+
+// regression targets are never stratified, even with several outputs, where
+// strata would group the samples by their largest target
+pub fn stratifies(task: TaskKind, requested: bool) -> bool {
+    requested && task == TaskKind::Classification
+}
+
+pub fn select(rows: &[Vec<f32>], indices: &[usize]) -> Vec<Vec<f32>> {
+    indices.iter().map(|&i| rows[i].clone()).collect()
+}
+
+// splits indices into (kept, held out) with round(len * ratio) held out per stratum;
+// a stratum of two or more samples always ends up on both sides
+fn partition(
+    indices: &[usize],
+    labels: &[Vec<f32>],
+    ratio: f32,
+    stratify: bool,
+    rng: &mut CgpRng,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut kept = Vec::new();
+    let mut held_out = Vec::new();
+    for mut stratum in strata(indices, labels, stratify) {
+        stratum.shuffle(rng);
+        let mut nbr_held_out = (stratum.len() as f32 * ratio).round() as usize;
+        if ratio > 0. && stratum.len() >= 2 {
+            nbr_held_out = nbr_held_out.clamp(1, stratum.len() - 1);
+        }
+        held_out.extend_from_slice(&stratum[..nbr_held_out]);
+        kept.extend_from_slice(&stratum[nbr_held_out..]);
+    }
+    kept.shuffle(rng);
+    held_out.shuffle(rng);
+    (kept, held_out)
+}
+
+// groups indices by the arg max of their one-hot label; single-column labels
+// form one stratum, callers disable stratify for multi-output regression
+fn strata(indices: &[usize], labels: &[Vec<f32>], stratify: bool) -> Vec<Vec<usize>> {
+    if !stratify || labels[0].len() < 2 {
+        return vec![indices.to_vec()];
+    }
+    let mut groups = vec![Vec::new(); labels[0].len()];
+    for &index in indices {
+        let label = &labels[index];
+        let mut class = 0;
+        for j in 1..label.len() {
+            if label[j] > label[class] {
+                class = j;
+            }
+        }
+        groups[class].push(index);
+    }
+    groups.retain(|x| !x.is_empty());
+    groups
+}
@@ -0,0 +1,515 @@
+use crate::datasets::loader::Samples;
+use crate::utils::random::{self, CgpRng};
+use rand::Rng;
+use std::f64::consts::PI;
+
+// sampling of a single variable, notation of McDermott et al., "Genetic
+// programming needs better benchmarks" (GECCO 2012)
+#[derive(Clone, Copy, Debug)]
+pub enum Sampling {
+    // U[min, max, n]: n values drawn uniformly at random
+    Uniform(f64, f64, usize),
+    // E[min, max, step]: evenly spaced values including both ends
+    Grid(f64, f64, f64),
+}
+
+pub struct Benchmark {
+    pub name: &'static str,
+    pub nbr_inputs: usize,
+    pub function: fn(&[f64]) -> f64,
+    // one sampling per input variable; uniform variables are drawn together,
+    // grids are combined into their cartesian product
+    pub train: Vec<Sampling>,
+    // None where no test set is published: a uniform training scheme is
+    // sampled again, a grid has no test set of its own and is split randomly
+    pub test: Option<Vec<Sampling>>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BenchmarkSettings {
+    // replaces n of every uniform sampling, grids keep their step
+    pub sample_count: Option<usize>,
+    pub seed: u64,
+}
+
+impl Benchmark {
+    // rows with an undefined target (e.g. the logarithm of a negative value) are dropped
+    pub fn generate(&self, settings: &BenchmarkSettings) -> (Samples, Option<Samples>) {
+        let mut rng = random::benchmark_rng(settings.seed);
+        let train = self.evaluate(sample(&self.train, settings.sample_count, &mut rng));
+        let test_scheme = match (&self.test, self.train[0]) {
+            (Some(test), _) => test,
+            // sampling the grid again would give the training points
+            (None, Sampling::Grid(_, _, _)) => return (train, None),
+            (None, Sampling::Uniform(_, _, _)) => &self.train,
+        };
+        let test = self.evaluate(sample(test_scheme, settings.sample_count, &mut rng));
+        (train, Some(test))
+    }
+
+    fn evaluate(&self, points: Vec<Vec<f64>>) -> Samples {
+        let mut data = Vec::with_capacity(points.len());
+        let mut labels = Vec::with_capacity(points.len());
+        for point in points {
+            let target = (self.function)(&point);
+            if !target.is_finite() {
+                continue;
+            }
+            data.push(point.iter().map(|x| *x as f32).collect());
+            labels.push(vec![target as f32]);
+        }
+        (data, labels)
+    }
+}
+
+fn sample(scheme: &[Sampling], sample_count: Option<usize>, rng: &mut CgpRng) -> Vec<Vec<f64>> {
+    match scheme[0] {
+        Sampling::Uniform(_, _, n) => {
+            let n = sample_count.unwrap_or(n);
+            let mut points = vec![Vec::with_capacity(scheme.len()); n];
+            for point in points.iter_mut() {
+                for sampling in scheme {
+                    match *sampling {
+                        Sampling::Uniform(min, max, _) => point.push(rng.gen_range(min..=max)),
+                        Sampling::Grid(_, _, _) => panic!("uniform and grid sampling cannot be mixed"),
+                    }
+                }
+            }
+            points
+        }
+        Sampling::Grid(_, _, _) => {
+            let mut points: Vec<Vec<f64>> = vec![Vec::new()];
+            for sampling in scheme {
+                let values = match *sampling {
+                    Sampling::Grid(min, max, step) => grid(min, max, step),
+                    Sampling::Uniform(_, _, _) => panic!("uniform and grid sampling cannot be mixed"),
+                };
+                let mut product = Vec::with_capacity(points.len() * values.len());
+                for point in &points {
+                    for value in &values {
+                        let mut extended = point.clone();
+                        extended.push(*value);
+                        product.push(extended);
+                    }
+                }
+                points = product;
+            }
+            points
+        }
+    }
+}
+
+fn grid(min: f64, max: f64, step: f64) -> Vec<f64> {
+    let nbr_values = ((max - min) / step).round() as usize + 1;
+    (0..nbr_values).map(|i| min + i as f64 * step).collect()
+}
+
+fn uniform(nbr_inputs: usize, min: f64, max: f64, n: usize) -> Vec<Sampling> {
+    vec![Sampling::Uniform(min, max, n); nbr_inputs]
+}
+
+fn even(nbr_inputs: usize, min: f64, max: f64, step: f64) -> Vec<Sampling> {
+    vec![Sampling::Grid(min, max, step); nbr_inputs]
+}
+
+fn keijzer_1_3(x: &[f64]) -> f64 {
+    0.3 * x[0] * (2. * PI * x[0]).sin()
+}
+
+fn salustowicz(x: f64) -> f64 {
+    (-x).exp() * x.powi(3) * x.cos() * x.sin() * (x.cos() * x.sin().powi(2) - 1.)
+}
+
+pub fn benchmarks() -> Vec<Benchmark> {
+    let korns_train = || uniform(5, -50., 50., 10000);
+    let korns_test = || Some(uniform(5, -50., 50., 10000));
+    vec![
+        Benchmark {
+            name: "koza-1",
+            nbr_inputs: 1,
+            function: |x| x[0].powi(4) + x[0].powi(3) + x[0].powi(2) + x[0],
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "koza-2",
+            nbr_inputs: 1,
+            function: |x| x[0].powi(5) - 2. * x[0].powi(3) + x[0],
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "koza-3",
+            nbr_inputs: 1,
+            function: |x| x[0].powi(6) - 2. * x[0].powi(4) + x[0].powi(2),
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-1",
+            nbr_inputs: 1,
+            function: |x| x[0].powi(3) + x[0].powi(2) + x[0],
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-2",
+            nbr_inputs: 1,
+            function: |x| x[0].powi(4) + x[0].powi(3) + x[0].powi(2) + x[0],
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-3",
+            nbr_inputs: 1,
+            function: |x| x[0].powi(5) + x[0].powi(4) + x[0].powi(3) + x[0].powi(2) + x[0],
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-4",
+            nbr_inputs: 1,
+            function: |x| x[0].powi(6) + x[0].powi(5) + x[0].powi(4) + x[0].powi(3) + x[0].powi(2) + x[0],
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-5",
+            nbr_inputs: 1,
+            function: |x| x[0].powi(2).sin() * x[0].cos() - 1.,
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-6",
+            nbr_inputs: 1,
+            function: |x| x[0].sin() + (x[0] + x[0].powi(2)).sin(),
+            train: uniform(1, -1., 1., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-7",
+            nbr_inputs: 1,
+            function: |x| (x[0] + 1.).ln() + (x[0].powi(2) + 1.).ln(),
+            train: uniform(1, 0., 2., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-8",
+            nbr_inputs: 1,
+            function: |x| x[0].sqrt(),
+            train: uniform(1, 0., 4., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-9",
+            nbr_inputs: 2,
+            function: |x| x[0].sin() + x[1].powi(2).sin(),
+            train: uniform(2, -1., 1., 100),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-10",
+            nbr_inputs: 2,
+            function: |x| 2. * x[0].sin() * x[1].cos(),
+            train: uniform(2, -1., 1., 100),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-11",
+            nbr_inputs: 2,
+            function: |x| x[0].powf(x[1]),
+            train: uniform(2, 0., 1., 100),
+            test: None,
+        },
+        Benchmark {
+            name: "nguyen-12",
+            nbr_inputs: 2,
+            function: |x| x[0].powi(4) - x[0].powi(3) + x[1].powi(2) / 2. - x[1],
+            train: uniform(2, -3., 3., 20),
+            test: None,
+        },
+        Benchmark {
+            name: "keijzer-1",
+            nbr_inputs: 1,
+            function: keijzer_1_3,
+            train: even(1, -1., 1., 0.1),
+            test: Some(even(1, -1., 1., 0.001)),
+        },
+        Benchmark {
+            name: "keijzer-2",
+            nbr_inputs: 1,
+            function: keijzer_1_3,
+            train: even(1, -2., 2., 0.1),
+            test: Some(even(1, -2., 2., 0.001)),
+        },
+        Benchmark {
+            name: "keijzer-3",
+            nbr_inputs: 1,
+            function: keijzer_1_3,
+            train: even(1, -3., 3., 0.1),
+            test: Some(even(1, -3., 3., 0.001)),
+        },
+        Benchmark {
+            name: "keijzer-4",
+            nbr_inputs: 1,
+            function: |x| salustowicz(x[0]),
+            train: even(1, 0., 10., 0.05),
+            test: Some(even(1, 0.05, 10.05, 0.05)),
+        },
+        Benchmark {
+            name: "keijzer-5",
+            nbr_inputs: 3,
+            function: |x| 30. * x[0] * x[2] / ((x[0] - 10.) * x[1].powi(2)),
+            train: vec![Sampling::Uniform(-1., 1., 1000), Sampling::Uniform(1., 2., 1000), Sampling::Uniform(-1., 1., 1000)],
+            test: Some(vec![
+                Sampling::Uniform(-1., 1., 10000),
+                Sampling::Uniform(1., 2., 10000),
+                Sampling::Uniform(-1., 1., 10000),
+            ]),
+        },
+        Benchmark {
+            name: "keijzer-6",
+            nbr_inputs: 1,
+            // harmonic number of floor(x)
+            function: |x| (1..=x[0].floor() as usize).map(|i| 1. / i as f64).sum(),
+            train: even(1, 1., 50., 1.),
+            test: Some(even(1, 1., 120., 1.)),
+        },
+        Benchmark {
+            name: "keijzer-7",
+            nbr_inputs: 1,
+            function: |x| x[0].ln(),
+            train: even(1, 1., 100., 1.),
+            test: Some(even(1, 1., 100., 0.1)),
+        },
+        Benchmark {
+            name: "keijzer-8",
+            nbr_inputs: 1,
+            function: |x| x[0].sqrt(),
+            train: even(1, 0., 100., 1.),
+            test: Some(even(1, 0., 100., 0.1)),
+        },
+        Benchmark {
+            name: "keijzer-9",
+            nbr_inputs: 1,
+            function: |x| x[0].asinh(),
+            train: even(1, 0., 100., 1.),
+            test: Some(even(1, 0., 100., 0.1)),
+        },
+        Benchmark {
+            name: "keijzer-10",
+            nbr_inputs: 2,
+            function: |x| x[0].powf(x[1]),
+            train: uniform(2, 0., 1., 100),
+            test: Some(even(2, 0., 1., 0.01)),
+        },
+        Benchmark {
+            name: "keijzer-11",
+            nbr_inputs: 2,
+            function: |x| x[0] * x[1] + ((x[0] - 1.) * (x[1] - 1.)).sin(),
+            train: uniform(2, -3., 3., 20),
+            test: Some(even(2, -3., 3., 0.01)),
+        },
+        Benchmark {
+            name: "keijzer-12",
+            nbr_inputs: 2,
+            function: |x| x[0].powi(4) - x[0].powi(3) + x[1].powi(2) / 2. - x[1],
+            train: uniform(2, -3., 3., 20),
+            test: Some(even(2, -3., 3., 0.01)),
+        },
+        Benchmark {
+            name: "keijzer-13",
+            nbr_inputs: 2,
+            function: |x| 6. * x[0].sin() * x[1].cos(),
+            train: uniform(2, -3., 3., 20),
+            test: Some(even(2, -3., 3., 0.01)),
+        },
+        Benchmark {
+            name: "keijzer-14",
+            nbr_inputs: 2,
+            function: |x| 8. / (2. + x[0].powi(2) + x[1].powi(2)),
+            train: uniform(2, -3., 3., 20),
+            test: Some(even(2, -3., 3., 0.01)),
+        },
+        Benchmark {
+            name: "keijzer-15",
+            nbr_inputs: 2,
+            function: |x| x[0].powi(3) / 5. + x[1].powi(3) / 2. - x[1] - x[0],
+            train: uniform(2, -3., 3., 20),
+            test: Some(even(2, -3., 3., 0.01)),
+        },
+        Benchmark {
+            name: "vladislavleva-1",
+            nbr_inputs: 2,
+            function: |x| (-(x[0] - 1.).powi(2)).exp() / (1.2 + (x[1] - 2.5).powi(2)),
+            train: uniform(2, 0.3, 4., 100),
+            test: Some(even(2, -0.2, 4.2, 0.1)),
+        },
+        Benchmark {
+            name: "vladislavleva-2",
+            nbr_inputs: 1,
+            function: |x| salustowicz(x[0]),
+            train: even(1, 0.05, 10., 0.1),
+            test: Some(even(1, -0.5, 10.5, 0.05)),
+        },
+        Benchmark {
+            name: "vladislavleva-3",
+            nbr_inputs: 2,
+            function: |x| salustowicz(x[0]) * (x[1] - 5.),
+            train: vec![Sampling::Grid(0.05, 10., 0.1), Sampling::Grid(0.05, 10.05, 2.)],
+            test: Some(vec![Sampling::Grid(-0.5, 10.5, 0.05), Sampling::Grid(-0.5, 10.5, 0.5)]),
+        },
+        Benchmark {
+            name: "vladislavleva-4",
+            nbr_inputs: 5,
+            function: |x| 10. / (5. + x.iter().map(|x| (x - 3.).powi(2)).sum::<f64>()),
+            train: uniform(5, 0.05, 6.05, 1024),
+            test: Some(uniform(5, -0.25, 6.35, 5000)),
+        },
+        Benchmark {
+            name: "vladislavleva-5",
+            nbr_inputs: 3,
+            function: |x| 30. * (x[0] - 1.) * (x[2] - 1.) / (x[1].powi(2) * (x[0] - 10.)),
+            train: vec![Sampling::Uniform(0.05, 2., 300), Sampling::Uniform(1., 2., 300), Sampling::Uniform(0.05, 2., 300)],
+            test: Some(vec![Sampling::Grid(-0.05, 2.1, 0.15), Sampling::Grid(0.95, 2.05, 0.1), Sampling::Grid(-0.05, 2.1, 0.15)]),
+        },
+        Benchmark {
+            name: "vladislavleva-6",
+            nbr_inputs: 2,
+            function: |x| 6. * x[0].sin() * x[1].cos(),
+            train: uniform(2, 0.1, 5.9, 30),
+            test: Some(even(2, -0.05, 6.05, 0.02)),
+        },
+        Benchmark {
+            name: "vladislavleva-7",
+            nbr_inputs: 2,
+            function: |x| (x[0] - 3.) * (x[1] - 3.) + 2. * ((x[0] - 4.) * (x[1] - 4.)).sin(),
+            train: uniform(2, 0.05, 6.05, 300),
+            test: Some(uniform(2, -0.25, 6.35, 1000)),
+        },
+        Benchmark {
+            name: "vladislavleva-8",
+            nbr_inputs: 2,
+            function: |x| ((x[0] - 3.).powi(4) + (x[1] - 3.).powi(3) - (x[1] - 3.)) / ((x[1] - 2.).powi(4) + 10.),
+            train: uniform(2, 0.05, 6.05, 50),
+            test: Some(even(2, -0.25, 6.35, 0.2)),
+        },
+        Benchmark {
+            name: "korns-1",
+            nbr_inputs: 5,
+            function: |x| 1.57 + 24.3 * x[3],
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-2",
+            nbr_inputs: 5,
+            function: |x| 0.23 + 14.2 * (x[3] + x[1]) / (3. * x[4]),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-3",
+            nbr_inputs: 5,
+            function: |x| -5.41 + 4.9 * (x[3] - x[0] + x[1] / x[4]) / (3. * x[4]),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-4",
+            nbr_inputs: 5,
+            function: |x| -2.3 + 0.13 * x[2].sin(),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-5",
+            nbr_inputs: 5,
+            function: |x| 3. + 2.13 * x[4].ln(),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-6",
+            nbr_inputs: 5,
+            function: |x| 1.3 + 0.13 * x[0].sqrt(),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-7",
+            nbr_inputs: 5,
+            function: |x| 213.80940889 * (1. - (-0.54723748542 * x[0]).exp()),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-8",
+            nbr_inputs: 5,
+            function: |x| 6.87 + 11. * (7.23 * x[0] * x[3] * x[4]).sqrt(),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-9",
+            nbr_inputs: 5,
+            function: |x| x[0].sqrt() / x[1].ln() * x[2].exp() / x[3].powi(2),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-10",
+            nbr_inputs: 5,
+            function: |x| 0.81 + 24.3 * (2. * x[1] + 3. * x[2].powi(2)) / (4. * x[3].powi(3) + 5. * x[4].powi(4)),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-11",
+            nbr_inputs: 5,
+            function: |x| 6.87 + 11. * (7.23 * x[0].powi(3)).cos(),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-12",
+            nbr_inputs: 5,
+            function: |x| 2. - 2.1 * (9.8 * x[0]).cos() * (1.3 * x[4]).sin(),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-13",
+            nbr_inputs: 5,
+            function: |x| 32. - 3. * (x[0].tan() / x[1].tan()) * (x[2].tan() / x[3].tan()),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-14",
+            nbr_inputs: 5,
+            function: |x| 22. - 4.2 * (x[0].cos() - x[1].tan()) * (x[2].tanh() / x[3].sin()),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "korns-15",
+            nbr_inputs: 5,
+            function: |x| 12. - 6. * (x[0].tan() / x[1].exp()) * (x[2].ln() - x[3].tan()),
+            train: korns_train(),
+            test: korns_test(),
+        },
+        Benchmark {
+            name: "pagie-1",
+            nbr_inputs: 2,
+            function: |x| 1. / (1. + x[0].powi(-4)) + 1. / (1. + x[1].powi(-4)),
+            train: even(2, -5., 5., 0.4),
+            test: None,
+        },
+    ]
+}
+
+pub fn find(name: &str) -> Option<Benchmark> {
+    benchmarks().into_iter().find(|x| x.name == name)
+}
@@ -1,8 +1,20 @@
+use crate::cgp_es::functions::FunctionSet;
+use crate::datasets::dataset::TaskKind;
+use crate::utils::fitness_metrics::{ClassificationObjective, RegressionMetric};
+use crate::utils::pareto::ComplexityMeasure;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
-#[derive(Clone)]
+// the enums and the function set are written as their command line spelling
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CgpParameters {
+    // number of computational nodes, rows * columns
     pub graph_width: usize,
+    pub rows: usize,
+    pub columns: usize,
+    // how many previous columns a node can read from, the inputs count as one;
+    // 0 is no limit
+    pub levels_back: usize,
     pub iterations: usize,
     pub mu: usize,
     pub lambda: usize,
@@ -15,12 +27,43 @@ pub struct CgpParameters {
     pub beta:f32,
     pub roh:f32,
     pub distance_function:i32,
+    // regression uses metric on every output, classification the classification_objective
+    #[serde(with = "crate::utils::serde_str")]
+    pub task: TaskKind,
+    // fitness of regression tasks, averaged over the outputs
+    #[serde(with = "crate::utils::serde_str")]
+    pub metric: RegressionMetric,
+    #[serde(with = "crate::utils::serde_str")]
+    pub classification_objective: ClassificationObjective,
+    // fit intercept + slope * output to the labels before the metric
+    pub linear_scaling: bool,
+    // share of the cases used per generation by down-sampled lexicase (elitism_type 5)
+    pub lexicase_sample_rate: f32,
+    // second objective of the NSGA-II selection (elitism_type 6)
+    #[serde(with = "crate::utils::serde_str")]
+    pub complexity_measure: ComplexityMeasure,
+    // functions of the real-valued computational nodes
+    #[serde(with = "crate::utils::serde_str")]
+    pub function_set: FunctionSet,
+    // the last nbr_constants of the nbr_inputs input nodes hold evolved constants
+    pub nbr_constants: usize,
+    // constants start uniform in [-constant_range, constant_range]
+    pub constant_range: f32,
+    // standard deviation of the gaussian mutation step of a constant
+    pub constant_sigma: f32,
+    // levenberg-marquardt on the constants of the best offspring every
+    // constant_tuning_interval generations, 0 disables it
+    pub constant_tuning_interval: usize,
+    pub constant_tuning_iterations: usize,
 }
 
 impl Default for CgpParameters {
     fn default() -> Self {
         CgpParameters {
             graph_width: 0,
+            rows: 1,
+            columns: 0,
+            levels_back: 0,
             iterations: 1_000_000,
             mu: 1,
             lambda: 4,
@@ -33,6 +76,18 @@ impl Default for CgpParameters {
             beta: 1.,
             roh:1.,
             distance_function:0,
+            task: TaskKind::Regression,
+            metric: RegressionMetric::Mse,
+            classification_objective: ClassificationObjective::Mcc,
+            linear_scaling: false,
+            lexicase_sample_rate: 0.1,
+            complexity_measure: ComplexityMeasure::ActiveNodes,
+            function_set: FunctionSet::default(),
+            nbr_constants: 0,
+            constant_range: 1.,
+            constant_sigma: 0.1,
+            constant_tuning_interval: 0,
+            constant_tuning_iterations: 10,
         }
     }
 }
@@ -41,6 +96,9 @@ impl Display for CgpParameters {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "############ Parameters ############\n")?;
         write!(f, "graph_width: {}\n", self.graph_width)?;
+        writeln!(f, "rows: {}", self.rows)?;
+        writeln!(f, "columns: {}", self.columns)?;
+        writeln!(f, "levels_back: {}", self.levels_back)?;
         write!(f, "iterations: {}\n", self.iterations)?;
         write!(f, "mu: {}\n", self.mu)?;
         write!(f, "lambda: {}\n", self.lambda)?;
@@ -53,6 +111,18 @@ impl Display for CgpParameters {
         write!(f, "beta: {}\n", self.beta)?;
         write!(f, "roh: {}\n", self.roh)?;
         write!(f, "distance_function: {}\n", self.distance_function)?;
+        writeln!(f, "task: {}", self.task)?;
+        writeln!(f, "metric: {}", self.metric)?;
+        writeln!(f, "classification_objective: {}", self.classification_objective)?;
+        writeln!(f, "linear_scaling: {}", self.linear_scaling)?;
+        writeln!(f, "lexicase_sample_rate: {}", self.lexicase_sample_rate)?;
+        writeln!(f, "complexity_measure: {}", self.complexity_measure)?;
+        writeln!(f, "function_set: {}", self.function_set)?;
+        writeln!(f, "nbr_constants: {}", self.nbr_constants)?;
+        writeln!(f, "constant_range: {}", self.constant_range)?;
+        writeln!(f, "constant_sigma: {}", self.constant_sigma)?;
+        writeln!(f, "constant_tuning_interval: {}", self.constant_tuning_interval)?;
+        writeln!(f, "constant_tuning_iterations: {}", self.constant_tuning_iterations)?;
         write!(f, "#########################\n")
     }
 }
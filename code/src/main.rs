@@ -1,16 +1,29 @@
-use cgp::datasets::*;
+use cgp::cgp_es::functions::FunctionSet;
+use cgp::cgp_es::model::Model;
+use cgp::datasets;
+use cgp::datasets::dataset::TaskKind;
+use cgp::datasets::imputation::{ImputationStrategy, Imputer};
+use cgp::datasets::preprocessing::{Preprocessing, Preprocessor};
+use cgp::datasets::scaling::ScalingMethod;
+use cgp::datasets::split::{self, SplitConfig, SplitStrategy};
+use cgp::datasets::sr_benchmarks::BenchmarkSettings;
+use cgp::datasets::loader::{self, DatasetError, Samples};
 use cgp::global_params::CgpParameters;
-use cgp::utils::runner::Runner;
+use cgp::utils::fitness_metrics::{ClassificationObjective, RegressionMetric};
+use cgp::utils::pareto::ComplexityMeasure;
+use cgp::utils::random;
+use cgp::utils::runner::{ParetoMember, Runner};
 use clap::Parser;
 use float_eq::float_eq;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
 use plotly::common::Mode;
 use plotly::{Plot, Scatter};
 use plotly::color::NamedColor;
-use rand::thread_rng;
-use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
 
 #[derive(Parser)]
 #[clap(author, version, about, name = "testname")]
@@ -18,12 +31,113 @@ struct Args {
     #[arg(long, default_value_t = 0)]
     run_id: usize,
 
+    // name or id, see --list-datasets
+    #[arg(long, default_value = "koza")]
+    dataset: String,
+
+    #[arg(long)]
+    list_datasets: bool,
+
+    // mean, median, mode, constant[:<value>] or indicator
+    #[arg(long, default_value = "mean")]
+    imputation: ImputationStrategy,
+
+    // minmax, zscore, robust or none; defaults depend on the dataset
+    #[arg(long)]
+    scaling: Option<ScalingMethod>,
+
+    // regression targets only
+    #[arg(long)]
+    target_scaling: Option<ScalingMethod>,
+
+    #[arg(long, default_value_t = 0.2)]
+    test_ratio: f32,
+
+    // number of holdout splits, ignored for k-fold
+    #[arg(long, default_value_t = 10)]
+    repetitions: usize,
+
+    // 0: holdout, otherwise k-fold cross-validation
+    #[arg(long, default_value_t = 0)]
+    folds: usize,
+
+    #[arg(long, default_value_t = 1)]
+    cv_repeats: usize,
+
+    // taken from the training part, 0 disables it
+    #[arg(long, default_value_t = 0.)]
+    validation_ratio: f32,
+
+    #[arg(long)]
+    no_stratify: bool,
+
+    // seeds every random choice of the run: benchmark data, splits and the
+    // evolution of each repetition; random if not given, always written to the
+    // output file
+    #[arg(long)]
+    seed: Option<u64>,
+
+    // overrides the number of uniformly drawn points of the regression benchmarks
+    #[arg(long)]
+    benchmark_samples: Option<usize>,
+
+    // fitness of regression tasks: mse, rmse, mae, nmse, r2, huber[:<delta>] or
+    // medae; r2 minimises 1 - R², the R² itself is reported as R2_<repetition>
+    #[arg(long, default_value = "mse")]
+    metric: RegressionMetric,
+
+    // fitness of classification tasks: mcc, cross-entropy or brier; the test
+    // report always contains the arg max metrics
+    #[arg(long, default_value = "mcc")]
+    classification_objective: ClassificationObjective,
+
+    // functions of the computational nodes: default or a comma separated list of
+    // add, sub, mul, div, neg, sin, cos, tan, tanh, relu, exp, log, abs, sigmoid,
+    // sqrt, square, pow, min and max
+    #[arg(long, default_value = "default")]
+    functions: FunctionSet,
+
+    // constant input nodes with evolved values, placed after the data inputs
+    #[arg(long, default_value_t = 0)]
+    nbr_constants: usize,
+
+    // initial constants are uniform in [-constant_range, constant_range]
+    #[arg(long, default_value_t = 1.)]
+    constant_range: f32,
+
+    // standard deviation of the gaussian mutation of a constant
+    #[arg(long, default_value_t = 0.1)]
+    constant_sigma: f32,
+
+    // levenberg-marquardt on the constants of the best offspring every n
+    // generations (mu+lambda and mu,lambda, regression only), 0 disables it;
+    // each pass over the training data counts as a function evaluation
     #[arg(long, default_value_t = 0)]
-    dataset: usize,
+    constant_tuning_interval: usize,
+
+    #[arg(long, default_value_t = 10)]
+    constant_tuning_iterations: usize,
+
+    // least-squares intercept and slope on the output of regression tasks
+    #[arg(long)]
+    linear_scaling: bool,
 
     #[arg(long, default_value_t = 50)]
     nbr_nodes: usize,
 
+    // grid geometry of the real-valued cgp: nbr_nodes are laid out in rows, or
+    // rows * columns nodes if --columns is given
+    #[arg(long, default_value_t = 1)]
+    rows: usize,
+
+    #[arg(long)]
+    columns: Option<usize>,
+
+    // previous columns a node can read from, the inputs count as one; all of
+    // them if not given or 0
+    #[arg(long)]
+    levels_back: Option<usize>,
+
     #[arg(long, default_value_t = 2)]
     cgp_type: usize,
 
@@ -68,12 +182,40 @@ struct Args {
     #[arg(long, default_value_t = 4)]
     lambda:usize,
 
+    // 0: neutral search
+    // 1: random
+    // 2: fuss
+    // 3: lexicase
+    // 4: epsilon-lexicase
+    // 5: down-sampled epsilon-lexicase
+    // 6: nsga-ii over fitness and complexity, needs mu > 1
     #[arg(long, default_value_t = 0)]
     elitism_type:usize,
+
+    // share of the training cases per generation for elitism_type 5
+    #[arg(long, default_value_t = 0.1)]
+    lexicase_sample_rate:f32,
+
+    // second objective of elitism_type 6: active-nodes or expression-size
+    #[arg(long, default_value = "active-nodes")]
+    complexity:ComplexityMeasure,
+
+    // falls back to $CGP_DATA_ROOT, then src/datasets/Data
+    #[arg(long)]
+    data_root: Option<PathBuf>,
+
+    // worker threads for the evaluation, 0: one per core; the results do not
+    // depend on it
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
 }
 
 fn main() {
     let args = Args::parse();
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads)
+        .build_global()
+        .expect("cannot build the thread pool");
 
     if args.mutation_type == 1 {
         if float_eq!(args.mutation_prob, -1., abs <= 0.01) {
@@ -96,95 +238,104 @@ fn main() {
         _ => panic!(),
     };
 
-    let (base_data, base_label) = match args.dataset {
-        0 => koza::get_dataset(),
-        1 => pagie::get_dataset(),
-        2 => nguyen::get_dataset(),
-        3 => pollution::get_dataset(),
-        4 => analcatdata::get_dataset(),
-        5 => abalone::get_dataset(),
-        6 => bike_sharing_day::get_dataset(),
-        7 => bike_sharing_hour::get_dataset(),
-        8 => cal_housing::get_dataset(),
-        9 => diabetes::get_dataset(),
-        10 => energydata::get_dataset(),
-        11 => forestfires::get_dataset(),
-        12 => winequality_white::get_dataset(),
-        13 => winequality_red::get_dataset(),
-        14 => adult::get_dataset(),
-        15 => bach::get_dataset(),
-        16 => car::get_dataset(),
-        17 => chronic_kidney_disease::get_dataset(),
-        18 => forest::get_dataset(),
-        19 => human::get_dataset(),
-        20 => iris::get_dataset(),
-        21 => wall24::get_dataset(),
-        22 => wine_quality::get_dataset(),
-        _ => panic!("Wrong dataset"),
+    let data_root = loader::resolve_data_root(args.data_root.clone());
+    let seed = args.seed.unwrap_or_else(|| thread_rng().gen());
+    let benchmark_settings = BenchmarkSettings { sample_count: args.benchmark_samples, seed };
+    if args.list_datasets {
+        for (id, dataset) in datasets::registry(&benchmark_settings).iter().enumerate() {
+            println!("{:>2}  {:<24}{}", id, dataset.name(), dataset.task());
+        }
+        return;
+    }
+
+    let (dataset_id, dataset) = match datasets::find(&args.dataset, &benchmark_settings) {
+        Some(found) => found,
+        None => {
+            eprintln!("Unknown dataset {}, see --list-datasets", args.dataset);
+            process::exit(1);
+        }
+    };
+    let task = dataset.task();
+    // datasets with a published partition keep it, all others are split randomly below
+    let mut fixed_train_size = None;
+    let loaded: Result<Samples, DatasetError> = match dataset.load_partitioned(&data_root) {
+        Some(Ok(((mut data, mut labels), (test_data, test_labels)))) => {
+            fixed_train_size = Some(data.len());
+            data.extend(test_data);
+            labels.extend(test_labels);
+            Ok((data, labels))
+        }
+        Some(Err(err)) => Err(err),
+        None => dataset.load(&data_root),
+    };
+    let (base_data, base_label) = match loaded {
+        Ok(dataset) => dataset,
+        Err(err) => {
+            eprintln!("Could not load dataset {}: {}", dataset.name(), err);
+            process::exit(1);
+        }
+    };
+
+    let mut target_scaling = args.target_scaling.unwrap_or(dataset.default_target_scaling());
+    if task == TaskKind::Classification {
+        target_scaling = ScalingMethod::None;
+    }
+    let preprocessing = Preprocessing {
+        imputation: args.imputation.clone(),
+        scaling: args.scaling.unwrap_or(dataset.default_scaling()),
+        target_scaling,
     };
 
-    let (mut data, mut label, mut test_data, mut test_label) = train_test_split(base_data.clone(), base_label.clone());
-
-    let dataset_string = match args.dataset {
-        0 => "koza",
-        1 => "pagie",
-        2 => "nguyen",
-        3 => "pollution",
-        4 => "analcatdata",
-        5 => "abalone",
-        6 => "bike_sharing_day",
-        7 => "bike_sharing_hour",
-        8 => "cal_housing",
-        9 => "diabetes",
-        10 => "energydata",
-        11 => "forestfires",
-        12 => "winequality_white",
-        13 => "winequality_red",
-        14 => "adult",
-        15 => "bach",
-        16 => "car",
-        17 => "chronic_kidney_disease",
-        18 => "forest",
-        19 => "human",
-        20 => "iris",
-        21 => "wall24",
-        22 => "wine_quality",
-        _ => panic!("Wrong dataset"),
+    let strategy = if let Some(train_size) = fixed_train_size {
+        SplitStrategy::Fixed { train_size, repetitions: args.repetitions }
+    } else if args.folds > 0 {
+        SplitStrategy::KFold { folds: args.folds, repeats: args.cv_repeats }
+    } else {
+        SplitStrategy::Holdout { test_ratio: args.test_ratio, repetitions: args.repetitions }
     };
+    let split_config = SplitConfig {
+        strategy,
+        validation_ratio: args.validation_ratio,
+        stratify: split::stratifies(task, !args.no_stratify),
+        seed,
+    };
+    let splits = split::make_splits(&split_config, &base_label);
+
 
     let mut graph_dir:String = String::new();
-    if args.dataset < 14{
+    if task == TaskKind::Regression {
         if args.cgp_type == 1{
-            graph_dir = "dataset_type_regression/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/" + &args.mu.to_string() + "," + &args.lambda.to_string()
+            graph_dir = "dataset_type_regression/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/" + &args.mu.to_string() + "," + &args.lambda.to_string()
         }else if args.cgp_type == 0{
-            graph_dir = "dataset_type_classification/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/" + &args.mu.to_string() + "+" + &args.lambda.to_string()
+            graph_dir = "dataset_type_classification/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/" + &args.mu.to_string() + "+" + &args.lambda.to_string()
         }else {
             if args.ant_type == 0 {
-                graph_dir = "dataset_type_regression/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "_tau_0_" + &args.tau_0.to_string() + "_alpha_" + &args.alpha.to_string() + "_beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
+                graph_dir = "dataset_type_regression/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "_tau_0_" + &args.tau_0.to_string() + "_alpha_" + &args.alpha.to_string() + "_beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
             } else if args.ant_type == 2 {
-                graph_dir = "dataset_type_regression/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
+                graph_dir = "dataset_type_regression/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
             } else {
-                graph_dir = "dataset_type_regression/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "tau_0_" + &args.tau_0.to_string() + "_beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
+                graph_dir = "dataset_type_regression/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "tau_0_" + &args.tau_0.to_string() + "_beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
             }
         }
     }else{
         if args.cgp_type == 1{
-            graph_dir = "dataset_type_classification/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/" + &args.mu.to_string() + "," + &args.lambda.to_string()
+            graph_dir = "dataset_type_classification/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/" + &args.mu.to_string() + "," + &args.lambda.to_string()
         }else if args.cgp_type == 0{
-            graph_dir = "dataset_type_classification/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/" + &args.mu.to_string() + "+" + &args.lambda.to_string()
+            graph_dir = "dataset_type_classification/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/" + &args.mu.to_string() + "+" + &args.lambda.to_string()
         }else{
             if args.ant_type == 0{
-                graph_dir = "dataset_type_classification/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "tau_0_" + &args.tau_0.to_string() + "_alpha_" + &args.alpha.to_string() + "_beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
+                graph_dir = "dataset_type_classification/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "tau_0_" + &args.tau_0.to_string() + "_alpha_" + &args.alpha.to_string() + "_beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
             }else if args.ant_type == 2{
-                graph_dir = "dataset_type_classification/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
+                graph_dir = "dataset_type_classification/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
             }else{
-                graph_dir = "dataset_type_classification/dataset_id_".to_string() + &args.dataset.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "tau_0_" + &args.tau_0.to_string() + "_beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
+                graph_dir = "dataset_type_classification/dataset_id_".to_string() + &dataset_id.to_string() + "/cgp_type_" + cgp_type + "/ant_type_" + _ant_type + "/one_table_" + &args.one_table.to_string() + "/global_best_" + &args.use_global_best.to_string() + "/distance_function_" + &args.distance_function.to_string() + "/nbr_nodes_" + &args.nbr_nodes.to_string() + "_popsize_" + &args.population_size.to_string() + "tau_0_" + &args.tau_0.to_string() + "_beta_" + &args.beta.to_string() + "_roh_" + &args.roh.to_string();
             }
         }
     }
 
 
     fs::create_dir_all(graph_dir.clone()).expect("cannot create dir");
+    let feature_names = dataset.feature_names(&data_root);
     let mut params = CgpParameters::default();
 
     params.tau_0 = args.tau_0;
@@ -196,6 +347,18 @@ fn main() {
     params.beta = args.beta;
     params.roh = args.roh;
     params.distance_function = args.distance_function;
+    params.task = task;
+    params.metric = args.metric;
+    params.classification_objective = args.classification_objective;
+    params.linear_scaling = args.linear_scaling;
+    params.lexicase_sample_rate = args.lexicase_sample_rate;
+    params.complexity_measure = args.complexity;
+    params.function_set = args.functions.clone();
+    params.nbr_constants = args.nbr_constants;
+    params.constant_range = args.constant_range;
+    params.constant_sigma = args.constant_sigma;
+    params.constant_tuning_interval = args.constant_tuning_interval;
+    params.constant_tuning_iterations = args.constant_tuning_iterations;
 
     if args.cgp_type == 2 {
         params.lambda = args.population_size - 1;
@@ -204,15 +367,21 @@ fn main() {
         params.mu = args.mu;
         params.lambda = args.lambda;
     }
+    if args.elitism_type == 6 && params.mu < 2 {
+        panic!("elitism_type 6 (nsga-ii) needs mu > 1");
+    }
 
 
-    params.graph_width = args.nbr_nodes;
-
-    let nbr_inputs = data[0].len();
-    let nbr_outputs = label[0].len();
+    if args.rows == 0 || (args.columns.is_none() && !args.nbr_nodes.is_multiple_of(args.rows)) {
+        panic!("nbr_nodes must be a multiple of rows");
+    }
+    params.rows = args.rows;
+    params.columns = args.columns.unwrap_or(args.nbr_nodes / args.rows);
+    params.graph_width = params.rows * params.columns;
+    params.levels_back = args.levels_back.unwrap_or(0);
 
-    params.nbr_inputs = nbr_inputs;
-    params.nbr_outputs = nbr_outputs;
+    // nbr_inputs is set per split, indicator columns depend on the training rows
+    params.nbr_outputs = base_label[0].len();
 
     let mut use_global_best_ant = true;
     if args.use_global_best == 0{
@@ -231,15 +400,46 @@ fn main() {
     let mut best_fitness_vals = Vec::new();
     let mut best_mae_vals = Vec::new();
     let mut active_nodes = Vec::new();
-    let mut positional_bias:Vec<f32> = vec![0.;params.nbr_inputs + params.graph_width];
+    let mut positional_bias:Vec<f32> = Vec::new();
     let mut end_at = Vec::new();
     let mut iterations_till_best = Vec::new();
     let mut total_func_evals_till_best:Vec<f32> = Vec::new();
+    // without the offspring that kept their parent's active graph
+    let mut effective_func_evals_till_best:Vec<f32> = Vec::new();
     let mut final_fitnesses = Vec::new();
+    let mut validation_fitnesses = Vec::new();
     let mut output = File::create(graph_dir.clone() + "/run_" + &args.run_id.to_string() +  "_fitness_and_func_evals").expect("cannot create file");
+    writeln!(output, "Seed: {}", seed).expect("unable to write");
+    if task == TaskKind::Regression {
+        writeln!(output, "Metric: {}", params.metric).expect("unable to write");
+    } else {
+        writeln!(output, "Objective: {}", params.classification_objective).expect("unable to write");
+    }
 
-    for z in 0..10 {
-        (data, label, test_data, test_label) = train_test_split(base_data.clone(), base_label.clone());
+    for (z, current_split) in splits.iter().enumerate() {
+        let mut data = split::select(&base_data, &current_split.train);
+        let mut label = split::select(&base_label, &current_split.train);
+        let mut test_data = split::select(&base_data, &current_split.test);
+        let mut test_label = split::select(&base_label, &current_split.test);
+        let mut validation_data = split::select(&base_data, &current_split.validation);
+        let mut validation_label = split::select(&base_label, &current_split.validation);
+        let preprocessor = Preprocessor::fit(&preprocessing, &data, &label);
+        let mut imputed_counts = preprocessor.transform(&mut data, &mut label);
+        for counts in [
+            preprocessor.transform(&mut test_data, &mut test_label),
+            preprocessor.transform(&mut validation_data, &mut validation_label),
+        ] {
+            for j in 0..imputed_counts.len() {
+                imputed_counts[j] += counts[j];
+            }
+        }
+        write_imputation_report(&graph_dir, args.run_id, z, &preprocessor.imputer, &imputed_counts, &feature_names);
+        params.nbr_inputs = data[0].len() + params.nbr_constants;
+        if positional_bias.len() < params.nbr_inputs + params.graph_width {
+            positional_bias.resize(params.nbr_inputs + params.graph_width, 0.);
+        }
+        let preprocessing_file = graph_dir.clone() + "/run_" + &args.run_id.to_string() + "_repetition_" + &z.to_string() + "_preprocessing";
+        preprocessor.write(Path::new(&preprocessing_file)).expect("cannot write preprocessing parameters");
         let mut i = 0;
         // let mut prev_it: Vec<f32> = Vec::from(runner.get_best_fitness());
         let mut func_evals = 0;
@@ -254,27 +454,23 @@ fn main() {
             args.mutation_type,
             args.mutation_prob,
             use_global_best_ant,
-            args.elitism_type
+            args.elitism_type,
+            random::repetition_rng(seed, z),
         );
         let mut func_evals_best = 0;
+        let mut effective_func_evals = 0;
+        let mut effective_func_evals_best = 0;
         if args.cgp_type != 2 {
-            runner = Runner::new(
-                params.clone(),
-                data.clone(),
-                label.clone(),
-                args.mutation_type,
-                args.mutation_prob,
-                use_global_best_ant,
-                args.elitism_type,
-            );
             runner.new_parents_by_method(replace_parents);
             loop {
                 func_evals += params.lambda;
                 i += 1;
                 let fitness = runner.learn_step(replace_parents);
+                func_evals += runner.get_tuning_evals();
+                effective_func_evals += runner.get_effective_evals() + runner.get_tuning_evals();
                 if i == 1 {
                     best_fitness = fitness;
-                    if args.dataset < 14 {
+                    if task == TaskKind::Regression {
                         best_mae = runner.get_best_mae();
                     }
                 }
@@ -282,7 +478,8 @@ fn main() {
                     best_fitness = fitness;
                     pos_best_fitness = i;
                     func_evals_best = func_evals;
-                    if args.dataset < 14 {
+                    effective_func_evals_best = effective_func_evals;
+                    if task == TaskKind::Regression {
                         best_mae = runner.get_best_mae();
                     }
                 }
@@ -299,14 +496,14 @@ fn main() {
                 }
                 best_fitness_vals.push(best_fitness);
                 let mut current_maes = Vec::new();
-                if args.dataset < 14 {
+                if task == TaskKind::Regression {
                     best_mae_vals.push(best_mae);
                     current_maes = runner.get_maes();
                 }
                 let current_fitnesses = runner.get_fitnesses();
                 for j in 0..(params.mu + params.lambda){
                     fitness_vals.push(current_fitnesses[j]);
-                    if args.dataset < 14{
+                    if task == TaskKind::Regression {
                         mae_vals.push(current_maes[j]);
                     }
                 }
@@ -318,6 +515,7 @@ fn main() {
                     final_fitnesses.push(runner.evaluate_chromosomes_cgp(test_data.clone(), test_label.clone()));
                     iterations_till_best.push(pos_best_fitness as f32);
                     total_func_evals_till_best.push(func_evals_best as f32);
+                    effective_func_evals_till_best.push(effective_func_evals_best as f32);
                     end_at.push(i);
                     writeln!(output, "Fitness_{}: {}", z, final_fitnesses[z]).expect("unable to write");
                     writeln!(output, "Iterations_{}: {}", z, iterations_till_best[z]).expect("unable to write");
@@ -328,6 +526,7 @@ fn main() {
                     final_fitnesses.push(runner.evaluate_chromosomes_cgp(test_data.clone(), test_label.clone()));
                     iterations_till_best.push(pos_best_fitness as f32);
                     total_func_evals_till_best.push(func_evals_best as f32);
+                    effective_func_evals_till_best.push(effective_func_evals_best as f32);
                     end_at.push(i);
                     writeln!(output, "Fitness_{}: {}", z, final_fitnesses[z]).expect("unable to write");
                     writeln!(output, "Iterations_{}: {}", z, iterations_till_best[z]).expect("unable to write");
@@ -335,17 +534,8 @@ fn main() {
                 }
             }
         } else {
-            runner = Runner::new(
-                params.clone(),
-                data.clone(),
-                label.clone(),
-                args.mutation_type,
-                args.mutation_prob,
-                use_global_best_ant,
-                args.elitism_type,
-            );
             if args.one_table == 0 {
-                let mut pheromone_table_functions = vec![vec![params.tau_0; 14]; params.graph_width];
+                let mut pheromone_table_functions = vec![vec![params.tau_0; params.function_set.len()]; params.graph_width];
                 let mut pheromone_table_connections = vec![vec![0.; (params.nbr_inputs + params.graph_width).pow(2)]; params.graph_width + params.nbr_outputs];
                 runner.initialize_pheromone_table(&mut pheromone_table_connections);
                 if args.ant_type == 2 {
@@ -357,14 +547,14 @@ fn main() {
                     let current_fitness = runner.ant_learn_two_tables(&mut pheromone_table_functions, &mut pheromone_table_connections, args.ant_type);
                     if i == 1 {
                         best_fitness = current_fitness;
-                        if args.dataset < 14 {
+                        if task == TaskKind::Regression {
                             best_mae = runner.get_best_mae();
                         }
                     }
                     if best_fitness > current_fitness {
                         best_fitness = current_fitness;
                         func_evals_best = func_evals;
-                        if args.dataset < 14 {
+                        if task == TaskKind::Regression {
                             best_mae = runner.get_best_mae();
                         }
                         pos_best_fitness = i;
@@ -382,14 +572,14 @@ fn main() {
                     }
                     best_fitness_vals.push(best_fitness);
                     let mut current_maes = Vec::new();
-                    if args.dataset < 14 {
+                    if task == TaskKind::Regression {
                         best_mae_vals.push(best_mae);
                         current_maes = runner.get_maes();
                     }
                     let current_fitnesses = runner.get_fitnesses();
                     for j in 0..(params.mu + params.lambda){
                         fitness_vals.push(current_fitnesses[j]);
-                        if args.dataset < 14{
+                        if task == TaskKind::Regression {
                             mae_vals.push(current_maes[j]);
                         }
                     }
@@ -399,6 +589,7 @@ fn main() {
                         end_at.push(i);
                         iterations_till_best.push(pos_best_fitness as f32);
                         total_func_evals_till_best.push(func_evals_best as f32);
+                        effective_func_evals_till_best.push(func_evals_best as f32);
                         writeln!(output, "Fitness_{}: {}", z, final_fitnesses[z]).expect("unable to write");
                         writeln!(output, "Iterations_{}: {}", z, iterations_till_best[z]).expect("unable to write");
                         break;
@@ -409,6 +600,7 @@ fn main() {
                         end_at.push(i);
                         iterations_till_best.push(pos_best_fitness as f32);
                         total_func_evals_till_best.push(func_evals_best as f32);
+                        effective_func_evals_till_best.push(func_evals_best as f32);
                         writeln!(output, "Fitness_{}: {}", z, final_fitnesses[z]).expect("unable to write");
                         writeln!(output, "Iterations_{}: {}", z, iterations_till_best[z]).expect("unable to write");
                         break;
@@ -416,7 +608,9 @@ fn main() {
                     last_fitness_change += 1;
                 }
             } else {
-                let mut pheromone_table = vec![vec![0.; (params.nbr_inputs + params.graph_width).pow(2) * 4 + (params.nbr_inputs + params.graph_width) * 10]; params.graph_width + params.nbr_outputs];
+                let nbr_binary = params.function_set.ids_with_arity(2).len();
+                let nbr_unary = params.function_set.ids_with_arity(1).len();
+                let mut pheromone_table = vec![vec![0.; (params.nbr_inputs + params.graph_width).pow(2) * nbr_binary + (params.nbr_inputs + params.graph_width) * nbr_unary]; params.graph_width + params.nbr_outputs];
                 runner.init_one_pheromone_table(&mut pheromone_table);
                 loop {
                     i += 1;
@@ -424,13 +618,14 @@ fn main() {
                     let current_fitness = runner.ant_learn_one_table(&mut pheromone_table, args.ant_type);
                     if i == 1 {
                         best_fitness = current_fitness;
-                        if args.dataset < 14 {
+                        if task == TaskKind::Regression {
                             best_mae = runner.get_best_mae();
                         }
                     }
                     if best_fitness > current_fitness {
                         best_fitness = current_fitness;
-                        if args.dataset < 14 {
+                        func_evals_best = func_evals;
+                        if task == TaskKind::Regression {
                             best_mae = runner.get_best_mae();
                         }
                         last_fitness_change = 0;
@@ -447,14 +642,14 @@ fn main() {
                     }
                     best_fitness_vals.push(best_fitness);
                     let mut current_maes = Vec::new();
-                    if args.dataset < 14 {
+                    if task == TaskKind::Regression {
                         best_mae_vals.push(best_mae);
                         current_maes = runner.get_maes();
                     }
                     let current_fitnesses = runner.get_fitnesses();
                     for j in 0..(params.mu + params.lambda){
                         fitness_vals.push(current_fitnesses[j]);
-                        if args.dataset < 14{
+                        if task == TaskKind::Regression {
                             mae_vals.push(current_maes[j]);
                         }
                     }
@@ -463,6 +658,7 @@ fn main() {
                         end_at.push(i);
                         iterations_till_best.push(pos_best_fitness as f32);
                         total_func_evals_till_best.push(func_evals_best as f32);
+                        effective_func_evals_till_best.push(func_evals_best as f32);
                         writeln!(output, "Fitness_{}: {}", z, final_fitnesses[z]).expect("unable to write");
                         writeln!(output, "Iterations_{}: {}", z, iterations_till_best[z]).expect("unable to write");
                         break;
@@ -472,6 +668,7 @@ fn main() {
                         end_at.push(i);
                         iterations_till_best.push(pos_best_fitness as f32);
                         total_func_evals_till_best.push(func_evals_best as f32);
+                        effective_func_evals_till_best.push(func_evals_best as f32);
                         writeln!(output, "Fitness_{}: {}", z, final_fitnesses[z]).expect("unable to write");
                         writeln!(output, "Iterations_{}: {}", z, iterations_till_best[z]).expect("unable to write");
                         break;
@@ -480,6 +677,31 @@ fn main() {
                 }
             }
         }
+        if task == TaskKind::Regression && params.nbr_outputs > 1 {
+            let output_errors: Vec<String> = runner.get_best_output_errors().iter().map(|x| x.to_string()).collect();
+            writeln!(output, "Output_errors_{}: {}", z, output_errors.join(" ")).expect("unable to write");
+        }
+        if task == TaskKind::Regression && params.metric == RegressionMetric::RSquared {
+            writeln!(output, "R2_{}: {}", z, params.metric.report(final_fitnesses[z])).expect("unable to write");
+        }
+        if task == TaskKind::Classification {
+            let report_file = graph_dir.clone() + "/run_" + &args.run_id.to_string() + "_repetition_" + &z.to_string() + "_classification_report";
+            let mut report = File::create(report_file).expect("cannot create file");
+            write!(report, "{}", runner.confusion_matrix(test_data.clone(), test_label.clone())).expect("unable to write");
+        }
+        if args.elitism_type == 6 {
+            let front_file = graph_dir.clone() + "/run_" + &args.run_id.to_string() + "_repetition_" + &z.to_string() + "_pareto_front";
+            write_pareto_front(&front_file, &runner.pareto_front(test_data.clone(), test_label.clone()));
+        }
+        // the best individual on the training data, with the preprocessing to
+        // apply it to raw data; cgp_es::model::Model::load restores it
+        let model_file = graph_dir.clone() + "/run_" + &args.run_id.to_string() + "_repetition_" + &z.to_string() + "_model.json";
+        let model = Model::new(runner.best_chromosome(data.clone(), label.clone()), preprocessor, dataset.name(), seed, z);
+        model.save(Path::new(&model_file)).expect("cannot write model");
+        if !validation_data.is_empty() {
+            validation_fitnesses.push(runner.evaluate_chromosomes_cgp(validation_data, validation_label));
+            writeln!(output, "Validation_{}: {}", z, validation_fitnesses[z]).expect("unable to write");
+        }
     }
 
     let mut min_iters = 10000000000;
@@ -505,7 +727,7 @@ fn main() {
     let mut best_fitness_iteration:Vec<f32> = Vec::new();
     let mut best_global_fitnesses:Vec<f32> = Vec::new();
     let popsize = params.mu + params.lambda;
-    if args.dataset < 14 {
+    if task == TaskKind::Regression {
         for j in 0..min_iters {
             let mut fitness: Vec<f32> = Vec::new();
             let mut mae: Vec<f32> = Vec::new();
@@ -635,7 +857,7 @@ fn main() {
         }
     }
 
-    if args.dataset < 14{
+    if task == TaskKind::Regression {
         let mut plot = Plot::new();
         let trace = Scatter::new(Vec::from_iter(0..avg_mae.len()), avg_mae)
             .mode(Mode::Lines).name("average mae").line(plotly::common::Line::new().color(color));
@@ -724,32 +946,67 @@ fn main() {
 
     writeln!(output, "Fitness: {}", mean_fitness).expect("unable to write");
     writeln!(output, "Fitness_std_dev: {}", std_dev_fitness).expect("unable to write");
+    if task == TaskKind::Regression && params.metric == RegressionMetric::RSquared {
+        writeln!(output, "R2: {}", params.metric.report(mean_fitness)).expect("unable to write");
+    }
     writeln!(output, "Iterations: {}", mean_iterations).expect("unable to write");
     writeln!(output, "Iterations_std_dev: {}", std_dev_iter).expect("unable to write");
     writeln!(output, "Func_evals: {}", mean_evals).expect("unable to write");
     writeln!(output, "Func_evals_std_dev: {}", std_dev_eval).expect("unable to write");
+    let mean_effective_evals =
+        effective_func_evals_till_best.iter().sum::<f32>() / effective_func_evals_till_best.len() as f32;
+    let std_dev_effective_evals = (effective_func_evals_till_best
+        .iter()
+        .map(|x| (x - mean_effective_evals).powi(2))
+        .sum::<f32>()
+        / effective_func_evals_till_best.len() as f32)
+        .sqrt();
+    writeln!(output, "Effective_func_evals: {}", mean_effective_evals).expect("unable to write");
+    writeln!(output, "Effective_func_evals_std_dev: {}", std_dev_effective_evals).expect("unable to write");
     writeln!(output, "Fastest fund solution: {}", min_iters).expect("unable to write");
+    if !validation_fitnesses.is_empty() {
+        let mean_validation = validation_fitnesses.iter().sum::<f32>() / validation_fitnesses.len() as f32;
+        let std_dev_validation = (validation_fitnesses.iter().map(|x| (x - mean_validation).powi(2)).sum::<f32>()
+            / validation_fitnesses.len() as f32)
+            .sqrt();
+        writeln!(output, "Validation: {}", mean_validation).expect("unable to write");
+        writeln!(output, "Validation_std_dev: {}", std_dev_validation).expect("unable to write");
+    }
 }
 
 
-pub fn train_test_split(data: Vec<Vec<f32>>, label: Vec<Vec<f32>>) -> (Vec<Vec<f32>>, Vec<Vec<f32>>, Vec<Vec<f32>>, Vec<Vec<f32>>){
-    let train_size = (data.len() as f32 * 0.8).round() as usize;
-    let test_size = data.len() - train_size;
-
-    let mut train_data = vec![vec![0.; data[0].len()]; train_size];
-    let mut train_labels = vec![vec![0.; label[0].len()]; train_size];
-    let mut test_data = vec![vec![0.; data[0].len()]; test_size];
-    let mut test_labels = vec![vec![0.; label[0].len()]; test_size];
-
-    let mut random_split:Vec<_> = (0..label.len()).collect();
-    random_split.shuffle(&mut thread_rng());
-    for i in 0..train_size{
-        train_data[i] = data[random_split[i]].clone();
-        train_labels[i] = label[random_split[i]].clone();
+fn write_imputation_report(
+    graph_dir: &str,
+    run_id: usize,
+    repetition: usize,
+    imputer: &Imputer,
+    imputed_counts: &[usize],
+    feature_names: &Result<Vec<String>, DatasetError>,
+) {
+    let mut report = File::create(
+        graph_dir.to_string() + "/run_" + &run_id.to_string() + "_repetition_" + &repetition.to_string() + "_imputation",
+    )
+        .expect("cannot create file");
+    writeln!(report, "strategy: {}", imputer.strategy).expect("cannot write");
+    for (j, count) in imputed_counts.iter().enumerate() {
+        let name = match feature_names {
+            Ok(names) => names[j].clone(),
+            Err(_) => format!("x{}", j),
+        };
+        writeln!(report, "{} {} {}", name, count, imputer.fill_values[j]).expect("cannot write");
     }
-    for i in 0..test_size{
-        test_data[i] = data[random_split[train_size + i]].clone();
-        test_labels[i] = label[random_split[train_size + i]].clone();
+    let total: usize = imputed_counts.iter().sum();
+    if total > 0 {
+        println!("repetition {}: imputed {} missing values ({})", repetition, total, imputer.strategy);
     }
-    return (train_data, train_labels, test_data, test_labels);
-}
\ No newline at end of file
+}
+
+fn write_pareto_front(path: &str, front: &[ParetoMember]) {
+    let mut file = File::create(path).expect("cannot create file");
+    for member in front {
+        writeln!(file, "complexity: {}", member.complexity).expect("cannot write");
+        writeln!(file, "train_fitness: {}", member.train_fitness).expect("cannot write");
+        writeln!(file, "test_fitness: {}", member.test_fitness).expect("cannot write");
+        write!(file, "{}", member.chromosome).expect("cannot write");
+    }
+}
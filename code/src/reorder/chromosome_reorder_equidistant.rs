@@ -81,6 +81,15 @@ impl Chromosome {
     }
 
     pub fn evaluate(&mut self, inputs: &Array2<bool>, labels: &Array2<bool>) -> f32 {
+        self.evaluate_with_mask(inputs, labels, None)
+    }
+
+    // same as vanilla_cgp::chromosome::Chromosome::evaluate_masked
+    pub fn evaluate_masked(&mut self, inputs: &Array2<bool>, labels: &Array2<bool>, mask: &Array2<bool>) -> f32 {
+        self.evaluate_with_mask(inputs, labels, Some(mask))
+    }
+
+    fn evaluate_with_mask(&mut self, inputs: &Array2<bool>, labels: &Array2<bool>, mask: Option<&Array2<bool>>) -> f32 {
         // let active_nodes = self.get_active_nodes_id();
         // self.active_nodes = Some(self.get_active_nodes_id());
         self.get_active_nodes_id();
@@ -126,7 +135,10 @@ impl Chromosome {
         let outs = outputs.slice(s![output_start_id..output_end_id, ..]);
         let outs = outs.t();
 
-        let fitness = fitness_metrics::fitness_boolean(&outs, &labels);
+        let fitness = match mask {
+            Some(mask) => fitness_metrics::fitness_boolean_masked(&outs, &labels, mask),
+            None => fitness_metrics::fitness_boolean(&outs, &labels),
+        };
 
         return fitness;
     }
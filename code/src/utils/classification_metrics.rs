@@ -0,0 +1,152 @@
+use std::fmt::{Display, Formatter};
+
+// counts[true class][predicted class]; outputs and labels are one value per
+// class and a sample belongs to the arg max
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfusionMatrix {
+    pub counts: Vec<Vec<usize>>,
+}
+
+impl ConfusionMatrix {
+    pub fn new(outputs: &[Vec<f32>], labels: &[Vec<f32>]) -> ConfusionMatrix {
+        let nbr_classes = labels[0].len();
+        let mut counts = vec![vec![0; nbr_classes]; nbr_classes];
+        for (output, label) in outputs.iter().zip(labels) {
+            counts[get_argmax(label)][get_argmax(output)] += 1;
+        }
+        ConfusionMatrix { counts }
+    }
+
+    pub fn nbr_classes(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn nbr_samples(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    fn true_count(&self, class: usize) -> usize {
+        self.counts[class].iter().sum()
+    }
+
+    fn predicted_count(&self, class: usize) -> usize {
+        self.counts.iter().map(|row| row[class]).sum()
+    }
+
+    fn correct(&self) -> usize {
+        (0..self.nbr_classes()).map(|k| self.counts[k][k]).sum()
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        ratio(self.correct(), self.nbr_samples())
+    }
+
+    // mean recall over the classes that occur in the labels
+    pub fn balanced_accuracy(&self) -> f32 {
+        let present: Vec<usize> = (0..self.nbr_classes()).filter(|&k| self.true_count(k) > 0).collect();
+        if present.is_empty() {
+            return 0.;
+        }
+        present.iter().map(|&k| self.recall(k)).sum::<f32>() / present.len() as f32
+    }
+
+    // 0 if the class is never predicted
+    pub fn precision(&self, class: usize) -> f32 {
+        ratio(self.counts[class][class], self.predicted_count(class))
+    }
+
+    // 0 if the class never occurs
+    pub fn recall(&self, class: usize) -> f32 {
+        ratio(self.counts[class][class], self.true_count(class))
+    }
+
+    pub fn f1(&self, class: usize) -> f32 {
+        let precision = self.precision(class);
+        let recall = self.recall(class);
+        if precision + recall == 0. {
+            return 0.;
+        }
+        2. * precision * recall / (precision + recall)
+    }
+
+    pub fn macro_f1(&self) -> f32 {
+        (0..self.nbr_classes()).map(|k| self.f1(k)).sum::<f32>() / self.nbr_classes() as f32
+    }
+
+    // pooled over all classes; equals the accuracy when every sample has exactly one class
+    pub fn micro_f1(&self) -> f32 {
+        let true_positives = self.correct();
+        let false_positives = self.nbr_samples() - true_positives;
+        let false_negatives = false_positives;
+        ratio(2 * true_positives, 2 * true_positives + false_positives + false_negatives)
+    }
+
+    pub fn cohens_kappa(&self) -> f32 {
+        let n = self.nbr_samples() as f32;
+        let observed = self.accuracy();
+        let expected = (0..self.nbr_classes())
+            .map(|k| self.true_count(k) as f32 * self.predicted_count(k) as f32)
+            .sum::<f32>()
+            / (n * n);
+        if expected == 1. {
+            return 0.;
+        }
+        (observed - expected) / (1. - expected)
+    }
+
+    // multiclass Matthews correlation (Gorodkin's R_K), in [-1, 1]
+    pub fn mcc(&self) -> f32 {
+        let n = self.nbr_samples() as f32;
+        let mut covariance = self.correct() as f32 * n;
+        let mut predicted_square = 0.;
+        let mut true_square = 0.;
+        for k in 0..self.nbr_classes() {
+            let t = self.true_count(k) as f32;
+            let p = self.predicted_count(k) as f32;
+            covariance -= t * p;
+            predicted_square += p * p;
+            true_square += t * t;
+        }
+        let divisor = (n * n - predicted_square).sqrt() * (n * n - true_square).sqrt();
+        if divisor == 0. {
+            return 0.;
+        }
+        covariance / divisor
+    }
+}
+
+impl Display for ConfusionMatrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "accuracy: {}", self.accuracy())?;
+        writeln!(f, "balanced_accuracy: {}", self.balanced_accuracy())?;
+        writeln!(f, "macro_f1: {}", self.macro_f1())?;
+        writeln!(f, "micro_f1: {}", self.micro_f1())?;
+        writeln!(f, "cohens_kappa: {}", self.cohens_kappa())?;
+        writeln!(f, "mcc: {}", self.mcc())?;
+        writeln!(f, "class precision recall f1 support")?;
+        for k in 0..self.nbr_classes() {
+            writeln!(f, "{} {} {} {} {}", k, self.precision(k), self.recall(k), self.f1(k), self.true_count(k))?;
+        }
+        writeln!(f, "confusion_matrix (rows: true class, columns: predicted class)")?;
+        for row in &self.counts {
+            let row: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f32 {
+    if denominator == 0 {
+        return 0.;
+    }
+    numerator as f32 / denominator as f32
+}
+
+fn get_argmax(nets: &[f32]) -> usize {
+    nets.iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+        .unwrap()
+}
@@ -1,5 +1,7 @@
 use ndarray::prelude::*;
-// use ndarray::Zip;
+use ndarray::Zip;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 pub fn fitness_boolean(output: &ArrayView2<bool>, labels: &Array2<bool>) -> f32 {
     let mut fitness: i32 = 0;
@@ -15,3 +17,277 @@ pub fn fitness_boolean(output: &ArrayView2<bool>, labels: &Array2<bool>) -> f32
     let fitness = 1. - (fitness as f32 / labels.len() as f32) as f32;
     return fitness;
 }
+
+// like fitness_boolean, but outputs with a false mask entry (don't cares) are ignored
+pub fn fitness_boolean_masked(output: &ArrayView2<bool>, labels: &Array2<bool>, mask: &Array2<bool>) -> f32 {
+    let mut nbr_wrong = 0;
+    let mut nbr_cared = 0;
+    Zip::from(output).and(labels).and(mask).for_each(|x, y, cared| {
+        if *cared {
+            nbr_cared += 1;
+            if x != y {
+                nbr_wrong += 1;
+            }
+        }
+    });
+    if nbr_cared == 0 {
+        return 0.;
+    }
+    nbr_wrong as f32 / nbr_cared as f32
+}
+
+// regression losses over a single output column; lower is better and 0 is a
+// perfect fit, so the runners can minimise any of them
+pub trait FitnessMetric {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32;
+}
+
+pub struct Mse;
+pub struct Rmse;
+pub struct Mae;
+// mse divided by the variance of the labels
+pub struct Nmse;
+// minimised as 1 - R², see RegressionMetric::report
+pub struct RSquared;
+// quadratic below delta, linear above
+pub struct Huber {
+    pub delta: f32,
+}
+pub struct MedianAbsoluteError;
+
+impl FitnessMetric for Mse {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32 {
+        let sum: f32 = outputs.iter().zip(labels).map(|(x, y)| (x - y) * (x - y)).sum();
+        sum / outputs.len() as f32
+    }
+}
+
+impl FitnessMetric for Rmse {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32 {
+        Mse.evaluate(outputs, labels).sqrt()
+    }
+}
+
+impl FitnessMetric for Mae {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32 {
+        let sum: f32 = outputs.iter().zip(labels).map(|(x, y)| (x - y).abs()).sum();
+        sum / outputs.len() as f32
+    }
+}
+
+impl FitnessMetric for Nmse {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32 {
+        let variance = variance(labels);
+        if variance == 0. {
+            return Mse.evaluate(outputs, labels);
+        }
+        Mse.evaluate(outputs, labels) / variance
+    }
+}
+
+impl FitnessMetric for RSquared {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32 {
+        let residual: f32 = outputs.iter().zip(labels).map(|(x, y)| (x - y) * (x - y)).sum();
+        let total = variance(labels) * labels.len() as f32;
+        // R² is undefined for constant labels, only an exact fit counts then
+        if total == 0. {
+            return if residual == 0. { 0. } else { f32::INFINITY };
+        }
+        residual / total
+    }
+}
+
+impl FitnessMetric for Huber {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32 {
+        let sum: f32 = outputs
+            .iter()
+            .zip(labels)
+            .map(|(x, y)| {
+                let error = (x - y).abs();
+                if error <= self.delta {
+                    0.5 * error * error
+                } else {
+                    self.delta * (error - 0.5 * self.delta)
+                }
+            })
+            .sum();
+        sum / outputs.len() as f32
+    }
+}
+
+impl FitnessMetric for MedianAbsoluteError {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32 {
+        let mut errors: Vec<f32> = outputs.iter().zip(labels).map(|(x, y)| (x - y).abs()).collect();
+        if errors.iter().any(|x| x.is_nan()) {
+            return f32::NAN;
+        }
+        errors.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let middle = errors.len() / 2;
+        if errors.len().is_multiple_of(2) {
+            (errors[middle - 1] + errors[middle]) / 2.
+        } else {
+            errors[middle]
+        }
+    }
+}
+
+fn variance(values: &[f32]) -> f32 {
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    values.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / n
+}
+
+// the metric selected with --metric, stored in CgpParameters
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum RegressionMetric {
+    #[default]
+    Mse,
+    Rmse,
+    Mae,
+    Nmse,
+    RSquared,
+    Huber(f32),
+    MedianAbsoluteError,
+}
+
+impl FitnessMetric for RegressionMetric {
+    fn evaluate(&self, outputs: &[f32], labels: &[f32]) -> f32 {
+        match *self {
+            RegressionMetric::Mse => Mse.evaluate(outputs, labels),
+            RegressionMetric::Rmse => Rmse.evaluate(outputs, labels),
+            RegressionMetric::Mae => Mae.evaluate(outputs, labels),
+            RegressionMetric::Nmse => Nmse.evaluate(outputs, labels),
+            RegressionMetric::RSquared => RSquared.evaluate(outputs, labels),
+            RegressionMetric::Huber(delta) => Huber { delta }.evaluate(outputs, labels),
+            RegressionMetric::MedianAbsoluteError => MedianAbsoluteError.evaluate(outputs, labels),
+        }
+    }
+}
+
+impl RegressionMetric {
+    // a fitness in the units of the metric: R² for r2, which is minimised as
+    // 1 - R², the loss itself for the others
+    pub fn report(&self, fitness: f32) -> f32 {
+        match self {
+            RegressionMetric::RSquared => 1. - fitness,
+            _ => fitness,
+        }
+    }
+}
+
+impl FromStr for RegressionMetric {
+    type Err = String;
+
+    // mse, rmse, mae, nmse, r2, huber[:<delta>] or medae
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match s.split_once(':') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s, None),
+        };
+        match (name, value) {
+            ("mse", None) => Ok(RegressionMetric::Mse),
+            ("rmse", None) => Ok(RegressionMetric::Rmse),
+            ("mae", None) => Ok(RegressionMetric::Mae),
+            ("nmse", None) => Ok(RegressionMetric::Nmse),
+            ("r2", None) => Ok(RegressionMetric::RSquared),
+            ("huber", None) => Ok(RegressionMetric::Huber(1.)),
+            ("huber", Some(value)) => value
+                .parse::<f32>()
+                .ok()
+                .filter(|x| *x > 0.)
+                .map(RegressionMetric::Huber)
+                .ok_or_else(|| format!("invalid huber delta '{}'", value)),
+            ("medae", None) => Ok(RegressionMetric::MedianAbsoluteError),
+            _ => Err(format!(
+                "unknown metric '{}', expected mse, rmse, mae, nmse, r2, huber[:<delta>] or medae",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for RegressionMetric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegressionMetric::Mse => write!(f, "mse"),
+            RegressionMetric::Rmse => write!(f, "rmse"),
+            RegressionMetric::Mae => write!(f, "mae"),
+            RegressionMetric::Nmse => write!(f, "nmse"),
+            RegressionMetric::RSquared => write!(f, "r2"),
+            RegressionMetric::Huber(delta) => write!(f, "huber:{}", delta),
+            RegressionMetric::MedianAbsoluteError => write!(f, "medae"),
+        }
+    }
+}
+
+// fitness of classification tasks, selected with --classification-objective
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ClassificationObjective {
+    // 1 - |mcc| of the arg max outputs
+    #[default]
+    Mcc,
+    // of the softmax of the outputs against the one-hot labels
+    CrossEntropy,
+    Brier,
+}
+
+impl FromStr for ClassificationObjective {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mcc" => Ok(ClassificationObjective::Mcc),
+            "cross-entropy" => Ok(ClassificationObjective::CrossEntropy),
+            "brier" => Ok(ClassificationObjective::Brier),
+            _ => Err(format!("unknown classification objective '{}', expected mcc, cross-entropy or brier", s)),
+        }
+    }
+}
+
+impl Display for ClassificationObjective {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClassificationObjective::Mcc => write!(f, "mcc"),
+            ClassificationObjective::CrossEntropy => write!(f, "cross-entropy"),
+            ClassificationObjective::Brier => write!(f, "brier"),
+        }
+    }
+}
+
+pub fn softmax(values: &[f32]) -> Vec<f32> {
+    // shifting by the maximum keeps exp from overflowing
+    let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let exps: Vec<f32> = values.iter().map(|x| (x - max).exp()).collect();
+    let sum: f32 = exps.iter().sum();
+    exps.iter().map(|x| x / sum).collect()
+}
+
+// mean over the samples of cross_entropy_case
+pub fn cross_entropy(outputs: &[Vec<f32>], labels: &[Vec<f32>]) -> f32 {
+    let sum: f32 = outputs.iter().zip(labels).map(|(x, y)| cross_entropy_case(x, y)).sum();
+    sum / outputs.len() as f32
+}
+
+// probabilities are clamped so that a confident wrong answer costs a finite amount
+pub fn cross_entropy_case(output: &[f32], label: &[f32]) -> f32 {
+    let probabilities = softmax(output);
+    let mut loss = 0.;
+    for (p, y) in probabilities.iter().zip(label) {
+        if *y > 0. {
+            loss -= y * p.max(1e-7).ln();
+        }
+    }
+    loss
+}
+
+// mean over the samples of brier_case
+pub fn brier_score(outputs: &[Vec<f32>], labels: &[Vec<f32>]) -> f32 {
+    let sum: f32 = outputs.iter().zip(labels).map(|(x, y)| brier_case(x, y)).sum();
+    sum / outputs.len() as f32
+}
+
+// squared distance between the softmax and the one-hot label, in [0, 2]
+pub fn brier_case(output: &[f32], label: &[f32]) -> f32 {
+    let probabilities = softmax(output);
+    probabilities.iter().zip(label).map(|(p, y)| (p - y) * (p - y)).sum()
+}
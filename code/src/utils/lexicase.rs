@@ -0,0 +1,70 @@
+use crate::utils::random::CgpRng;
+use rand::seq::SliceRandom;
+
+// epsilon of each case (0 for cases not in cases): the median absolute deviation of
+// the pool's errors on it (La Cava et al., 2016); errors[i] are the case errors of
+// individual i
+pub fn epsilons(errors: &[&[f32]], pool: &[usize], cases: &[usize]) -> Vec<f32> {
+    let nbr_cases = errors.first().map_or(0, |x| x.len());
+    let mut epsilons = vec![0.; nbr_cases];
+    for &case in cases {
+        let case_errors: Vec<f32> = pool.iter().map(|&i| errors[i][case]).collect();
+        epsilons[case] = median_absolute_deviation(&case_errors);
+    }
+    epsilons
+}
+
+// keeps the candidates within epsilon of the best one on each case, in the given
+// order of cases, until a single candidate is left
+pub fn filter(errors: &[&[f32]], candidates: &mut Vec<usize>, cases: &[usize], epsilons: &[f32]) {
+    for &case in cases {
+        if candidates.len() == 1 {
+            break;
+        }
+        let best = candidates.iter().map(|&i| errors[i][case]).fold(f32::INFINITY, f32::min);
+        candidates.retain(|&i| errors[i][case] <= best + epsilons[case]);
+    }
+}
+
+// selects nbr_parents distinct individuals of the pool; every selection shuffles
+// the cases anew and chooses randomly among the survivors of filter
+pub fn select(
+    errors: &[&[f32]],
+    mut pool: Vec<usize>,
+    nbr_parents: usize,
+    cases: &mut [usize],
+    epsilons: &[f32],
+    rng: &mut CgpRng,
+) -> Vec<usize> {
+    let mut selected: Vec<usize> = Vec::with_capacity(nbr_parents);
+    for _ in 0..nbr_parents {
+        let mut candidates = pool.clone();
+        cases.shuffle(rng);
+        filter(errors, &mut candidates, cases, epsilons);
+        let chosen = *candidates.choose(rng).unwrap();
+        selected.push(chosen);
+        pool.retain(|&x| x != chosen);
+    }
+    selected
+}
+
+// of the finite values, 0 if there are none
+pub fn median_absolute_deviation(values: &[f32]) -> f32 {
+    let mut finite: Vec<f32> = values.iter().cloned().filter(|x| x.is_finite()).collect();
+    if finite.is_empty() {
+        return 0.;
+    }
+    let center = median(&mut finite);
+    let mut deviations: Vec<f32> = finite.iter().map(|x| (x - center).abs()).collect();
+    median(&mut deviations)
+}
+
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.
+    } else {
+        values[middle]
+    }
+}
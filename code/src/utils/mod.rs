@@ -1,7 +1,12 @@
 pub mod boolean_functions;
+pub mod classification_metrics;
 pub mod cycle_checker;
 pub mod fitness_metrics;
+pub mod lexicase;
 pub mod node_type;
+pub mod pareto;
+pub mod random;
 pub mod runner;
 pub mod runner_multiple_parents;
+pub mod serde_str;
 pub mod vect_difference;
@@ -0,0 +1,112 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// second objective of the multi-objective selection, next to the fitness
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ComplexityMeasure {
+    // active computational nodes
+    #[default]
+    ActiveNodes,
+    // nodes of the expression tree when shared subgraphs are written out
+    ExpressionSize,
+}
+
+impl FromStr for ComplexityMeasure {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active-nodes" => Ok(ComplexityMeasure::ActiveNodes),
+            "expression-size" => Ok(ComplexityMeasure::ExpressionSize),
+            _ => Err(format!("unknown complexity measure '{}', expected active-nodes or expression-size", s)),
+        }
+    }
+}
+
+impl Display for ComplexityMeasure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComplexityMeasure::ActiveNodes => write!(f, "active-nodes"),
+            ComplexityMeasure::ExpressionSize => write!(f, "expression-size"),
+        }
+    }
+}
+
+// all objectives are minimised
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    let mut strictly_better = false;
+    for (x, y) in a.iter().zip(b) {
+        if x > y {
+            return false;
+        }
+        if x < y {
+            strictly_better = true;
+        }
+    }
+    strictly_better
+}
+
+// fast non-dominated sorting (Deb et al., 2002); returns the fronts as indices
+// into objectives, the first front is the non-dominated one
+pub fn non_dominated_sort(objectives: &[Vec<f32>]) -> Vec<Vec<usize>> {
+    let n = objectives.len();
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut domination_count = vec![0; n];
+    let mut fronts: Vec<Vec<usize>> = vec![Vec::new()];
+    for i in 0..n {
+        for j in 0..n {
+            if dominates(&objectives[i], &objectives[j]) {
+                dominated_by[i].push(j);
+            } else if dominates(&objectives[j], &objectives[i]) {
+                domination_count[i] += 1;
+            }
+        }
+        if domination_count[i] == 0 {
+            fronts[0].push(i);
+        }
+    }
+    let mut current = 0;
+    while !fronts[current].is_empty() {
+        let mut next = Vec::new();
+        for &i in &fronts[current] {
+            for &j in &dominated_by[i] {
+                domination_count[j] -= 1;
+                if domination_count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        fronts.push(next);
+        current += 1;
+    }
+    fronts.pop();
+    fronts
+}
+
+// crowding distance of every member of front, in the order of front; the
+// boundary solutions of each objective get an infinite distance
+pub fn crowding_distance(objectives: &[Vec<f32>], front: &[usize]) -> Vec<f32> {
+    let mut distances = vec![0.; front.len()];
+    if front.len() < 3 {
+        return vec![f32::INFINITY; front.len()];
+    }
+    let nbr_objectives = objectives[front[0]].len();
+    let columns: Vec<Vec<f32>> =
+        (0..nbr_objectives).map(|m| front.iter().map(|&i| objectives[i][m]).collect()).collect();
+    for values in &columns {
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        let first = order[0];
+        let last = order[front.len() - 1];
+        distances[first] = f32::INFINITY;
+        distances[last] = f32::INFINITY;
+        let range = values[last] - values[first];
+        if range <= 0. || !range.is_finite() {
+            continue;
+        }
+        for k in 1..front.len() - 1 {
+            distances[order[k]] += (values[order[k + 1]] - values[order[k - 1]]) / range;
+        }
+    }
+    distances
+}
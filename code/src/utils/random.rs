@@ -0,0 +1,31 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+// the generator behind every random choice of a run; chacha gives the same
+// numbers for a seed on every platform and with later versions of rand
+pub type CgpRng = ChaCha8Rng;
+
+// one seed drives the whole run and every part draws from its own stream, so
+// that e.g. the number of repetitions does not change the benchmark data
+const BENCHMARK_STREAM: u64 = 0;
+const SPLIT_STREAM: u64 = 1;
+const FIRST_REPETITION_STREAM: u64 = 2;
+
+pub fn benchmark_rng(seed: u64) -> CgpRng {
+    stream(seed, BENCHMARK_STREAM)
+}
+
+pub fn split_rng(seed: u64) -> CgpRng {
+    stream(seed, SPLIT_STREAM)
+}
+
+// initialisation, mutation, selection and ant construction of one repetition
+pub fn repetition_rng(seed: u64, repetition: usize) -> CgpRng {
+    stream(seed, FIRST_REPETITION_STREAM + repetition as u64)
+}
+
+fn stream(seed: u64, stream: u64) -> CgpRng {
+    let mut rng = CgpRng::seed_from_u64(seed);
+    rng.set_stream(stream);
+    rng
+}
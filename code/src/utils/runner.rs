@@ -1,17 +1,34 @@
 use crate::global_params::CgpParameters as g_params;
 use crate::cgp_es::chromosome::Chromosome;
+use crate::cgp_es::evaluator::{self, EvaluationBuffer};
+use crate::datasets::dataset::TaskKind;
 use float_eq::float_eq;
+use ndarray::Array2;
 use rand::seq::SliceRandom;
 use std::fmt::{Display, Formatter};
 use rand::distributions::WeightedIndex;
 use rand::prelude::*;
-use rand::thread_rng;
+use rayon::prelude::*;
+use crate::utils::classification_metrics::ConfusionMatrix;
+use crate::cgp_es::node;
 use crate::utils::node_type::NodeType;
+use crate::utils::lexicase;
+use crate::utils::pareto;
+use crate::utils::random::CgpRng;
+
+pub struct ParetoMember {
+    pub chromosome: Chromosome,
+    pub complexity: f32,
+    pub train_fitness: f32,
+    pub test_fitness: f32,
+}
 
 pub struct Runner {
     params: g_params,
-    data: Vec<Vec<f32>>,
+    // column-major, see evaluator::to_columns
+    data: Array2<f32>,
     label: Vec<Vec<f32>>,
+    buffer: EvaluationBuffer,
     chromosomes: Vec<Chromosome>,
     best_fitness: f32,
     fitness_vals: Vec<f32>,
@@ -37,6 +54,27 @@ pub struct Runner {
     two_conn_constant:usize,
     elitism_type:usize,
     num_active_nodes:Vec<usize>,
+    // constants of the best ant so far, the ants sample around them
+    ant_constants:Vec<f32>,
+    generation:usize,
+    // graph evaluations of the constant tuning in the last generation
+    tuning_evals:usize,
+    // the training outputs of every node per chromosome, for the incremental
+    // evaluation of their offspring; only where node_outputs_valid is set
+    node_outputs:Vec<EvaluationBuffer>,
+    node_outputs_valid:Vec<bool>,
+    // offspring whose active graph was their origin's: their rows are the
+    // ones in the origin's buffer, copied only if they become a parent
+    shared_outputs:Vec<Option<usize>>,
+    // the chromosome each offspring was cloned from, None for the parents
+    origins:Vec<Option<usize>>,
+    // offspring whose active graph changed in the last generation
+    effective_evals:usize,
+    // every non-dominated (fitness, complexity) chromosome seen by the NSGA-II
+    // selection, one per objective vector
+    pareto_archive:Vec<(Chromosome, Vec<f32>)>,
+    // every random choice of the run, see utils::random
+    rng:CgpRng,
 }
 
 impl Display for Runner {
@@ -47,6 +85,7 @@ impl Display for Runner {
 }
 
 impl Runner {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         params: g_params,
         data: Vec<Vec<f32>>,
@@ -55,17 +94,20 @@ impl Runner {
         mut_prob: f32,
         global_best: bool,
         elitism_type: usize,
+        mut rng: CgpRng,
     ) -> Self {
         let mut chromosomes: Vec<Chromosome> = Vec::with_capacity(params.mu + params.lambda);
         let mut fitness_vals: Vec<f32> = Vec::with_capacity(params.mu + params.lambda);
         let best_fitnesses: Vec<f32> = vec![-1.; params.mu + params.lambda];
         let mut mae_vals: Vec<f32> = Vec::with_capacity(params.mu + params.lambda);
+        let data = evaluator::to_columns(&data);
+        let mut buffer = EvaluationBuffer::new();
 
         for _ in 0..(params.mu + params.lambda) {
-            let mut chromosome = Chromosome::new(params.clone());
-            let fitness = chromosome.evaluate(&data, &label);
+            let mut chromosome = Chromosome::new(params.clone(), &mut rng);
+            let fitness = chromosome.evaluate(&data, &label, &mut buffer);
             fitness_vals.push(fitness);
-            if label[0].len() == 1{
+            if params.task == TaskKind::Regression{
                 mae_vals.push(chromosome.get_mae())
             }
             chromosomes.push(chromosome);
@@ -80,20 +122,26 @@ impl Runner {
         let global_best_fitness = -1.;
         let global_best = global_best;
         let func_tau_max = 0.;
-        let func_tau_min = 1. / 14.;
+        let func_tau_min = 1. / params.function_set.len() as f32;
         let conn_tau_max = 0.;
         let conn_tau_min = 1. / 14.;
         let avg = 0.;
         let p_best = 0.;
         let all_active_nodes = Vec::new();
         let exploration_rate = 0.1;
-        let two_conn_constant = (params.nbr_inputs + params.graph_width).pow(2) * 4;
+        let two_conn_constant = (params.nbr_inputs + params.graph_width).pow(2) * params.function_set.ids_with_arity(2).len();
         let num_active_nodes:Vec<usize> = vec![0; 1];
+        let ant_constants = chromosomes[0].constants.clone();
+        let node_outputs = vec![EvaluationBuffer::new(); params.mu + params.lambda];
+        let node_outputs_valid = vec![false; params.mu + params.lambda];
+        let shared_outputs = vec![None; params.mu + params.lambda];
+        let origins = vec![None; params.mu + params.lambda];
 
         Self {
             params,
             data,
             label,
+            buffer,
             chromosomes,
             best_fitness,
             fitness_vals,
@@ -119,12 +167,23 @@ impl Runner {
             two_conn_constant,
             elitism_type,
             num_active_nodes,
+            ant_constants,
+            generation: 0,
+            tuning_evals: 0,
+            node_outputs,
+            node_outputs_valid,
+            shared_outputs,
+            origins,
+            effective_evals: 0,
+            pareto_archive: Vec::new(),
+            rng,
         }
     }
 
     pub fn learn_step(&mut self, replace_parents:bool) -> f32{
         self.mutate_chromosomes();
-        self.eval_chromosomes();
+        self.eval_offspring();
+        self.tune_best_offspring();
         self.get_active_nodes();
         self.new_parents_by_method(replace_parents);
         return self.best_fitness;
@@ -135,6 +194,10 @@ impl Runner {
             0 => self.new_parent_by_neutral_search(replace_parents),
             1 => self.new_random_parents(replace_parents),
             2 => self.new_parents_by_fuss(replace_parents),
+            3 => self.new_parents_by_lexicase(replace_parents, false, 1.),
+            4 => self.new_parents_by_lexicase(replace_parents, true, 1.),
+            5 => self.new_parents_by_lexicase(replace_parents, true, self.params.lexicase_sample_rate),
+            6 => self.new_parents_by_nsga2(replace_parents),
             _ => panic!("elitism_type not defined"),
         }
     }
@@ -142,7 +205,7 @@ impl Runner {
     fn new_random_parents(&mut self, replace_parents:bool){
         let mut new_parents:Vec<usize> = Vec::from_iter(0..(self.params.mu + self.params.lambda));
         if self.parent_ids.len() == 0{
-            new_parents = new_parents.choose_multiple(&mut thread_rng(), self.params.mu).cloned().collect();
+            new_parents = new_parents.choose_multiple(&mut self.rng, self.params.mu).cloned().collect();
             self.parent_ids = new_parents;
         }else{
             if replace_parents {
@@ -152,7 +215,7 @@ impl Runner {
                     new_parents.remove(self.parent_ids[i]);
                 }
             }
-            new_parents = new_parents.choose_multiple(&mut thread_rng(), self.params.mu).cloned().collect();
+            new_parents = new_parents.choose_multiple(&mut self.rng, self.params.mu).cloned().collect();
             self.parent_ids = new_parents;
         }
     }
@@ -179,11 +242,11 @@ impl Runner {
             observable_fitness_vals = self.fitness_vals.clone();
         }
         if min == max{
-            new_parents = new_parents_choices.choose_multiple(&mut thread_rng(), self.params.mu).cloned().collect();
+            new_parents = new_parents_choices.choose_multiple(&mut self.rng, self.params.mu).cloned().collect();
             self.parent_ids = new_parents;
         }else{
             for _ in 0..self.params.mu{
-                let val = thread_rng().gen_range(min..max);
+                let val = self.rng.gen_range(min..max);
                 let mut min_dist = (val - self.fitness_vals[new_parents_choices[0]]).abs();
                 let mut current_index:usize = 0;
                 for i in 1..new_parents_choices.len(){
@@ -199,6 +262,96 @@ impl Runner {
         }
     }
 
+    // lexicase selection of mu distinct parents: the candidates are filtered case
+    // by case in random order, keeping those within epsilon of the best error.
+    // With use_epsilon, epsilon is the median absolute deviation of the case's
+    // errors in the pool (La Cava et al., 2016). A sample_rate below 1 restricts
+    // every generation to a random subset of the cases (down-sampled lexicase).
+    fn new_parents_by_lexicase(&mut self, replace_parents: bool, use_epsilon: bool, sample_rate: f32) {
+        let rng = &mut self.rng;
+        let mut pool: Vec<usize> = Vec::from_iter(0..(self.params.mu + self.params.lambda));
+        if replace_parents {
+            pool.retain(|x| !self.parent_ids.contains(x));
+        }
+        let errors: Vec<&[f32]> = self.chromosomes.iter().map(|x| x.get_case_errors()).collect();
+        let nbr_cases = errors[0].len();
+        let mut cases: Vec<usize> = Vec::from_iter(0..nbr_cases);
+        if sample_rate < 1. {
+            let nbr_sampled = ((nbr_cases as f32 * sample_rate).round() as usize).clamp(1, nbr_cases);
+            cases = cases.choose_multiple(rng, nbr_sampled).cloned().collect();
+        }
+        let epsilons = if use_epsilon { lexicase::epsilons(&errors, &pool, &cases) } else { vec![0.; nbr_cases] };
+        let new_parents = lexicase::select(&errors, pool, self.params.mu, &mut cases, &epsilons, rng);
+        self.parent_ids = new_parents;
+    }
+
+    // NSGA-II environmental selection (Deb et al., 2002) over (fitness, complexity):
+    // whole fronts are taken in order and the last one is cut by crowding distance
+    fn new_parents_by_nsga2(&mut self, replace_parents: bool) {
+        let mut pool: Vec<usize> = Vec::from_iter(0..(self.params.mu + self.params.lambda));
+        let all_objectives = self.objectives(&pool);
+        self.update_pareto_archive(&all_objectives);
+        if replace_parents {
+            pool.retain(|x| !self.parent_ids.contains(x));
+        }
+        let objectives: Vec<Vec<f32>> = pool.iter().map(|&i| all_objectives[i].clone()).collect();
+        let mut new_parents: Vec<usize> = Vec::with_capacity(self.params.mu);
+        for front in pareto::non_dominated_sort(&objectives) {
+            if new_parents.len() + front.len() <= self.params.mu {
+                new_parents.extend(front.iter().map(|&x| pool[x]));
+                continue;
+            }
+            let distances = pareto::crowding_distance(&objectives, &front);
+            let mut order: Vec<usize> = (0..front.len()).collect();
+            order.shuffle(&mut self.rng);
+            order.sort_by(|&a, &b| distances[b].total_cmp(&distances[a]));
+            for k in order.into_iter().take(self.params.mu - new_parents.len()) {
+                new_parents.push(pool[front[k]]);
+            }
+            break;
+        }
+        self.parent_ids = new_parents;
+    }
+
+    // objectives has one entry per chromosome
+    fn update_pareto_archive(&mut self, objectives: &[Vec<f32>]) {
+        for (i, candidate) in objectives.iter().enumerate() {
+            if self.pareto_archive.iter().any(|(_, x)| x == candidate || pareto::dominates(x, candidate)) {
+                continue;
+            }
+            self.pareto_archive.retain(|(_, x)| !pareto::dominates(candidate, x));
+            self.pareto_archive.push((self.chromosomes[i].clone(), candidate.clone()));
+        }
+    }
+
+    // (fitness, complexity) of the given chromosomes
+    fn objectives(&mut self, ids: &[usize]) -> Vec<Vec<f32>> {
+        let measure = self.params.complexity_measure;
+        ids.iter()
+            .map(|&i| vec![self.fitness_vals[i], self.chromosomes[i].complexity(measure)])
+            .collect()
+    }
+
+    // the non-dominated chromosomes on the training data over the whole run
+    // (elitism_type 6 only), sorted by complexity and scored on the test data
+    pub fn pareto_front(&mut self, test_data: Vec<Vec<f32>>, test_labels: Vec<Vec<f32>>) -> Vec<ParetoMember> {
+        let mut front = self.pareto_archive.clone();
+        front.sort_by(|a, b| a.1[1].total_cmp(&b.1[1]));
+        let test_data = evaluator::to_columns(&test_data);
+        front
+            .into_iter()
+            .map(|(mut chromosome, objectives)| {
+                let test_fitness = chromosome.score(&test_data, &test_labels, &mut self.buffer);
+                ParetoMember {
+                    chromosome,
+                    complexity: objectives[1],
+                    train_fitness: objectives[0],
+                    test_fitness,
+                }
+            })
+            .collect()
+    }
+
     fn new_parent_by_neutral_search(&mut self, replace_parents:bool) {
         let mut new_parents_choices:Vec<usize> = Vec::from_iter(0..(self.params.mu + self.params.lambda));
         let mut remaining_fitness_vals = self.fitness_vals.clone();
@@ -257,23 +410,65 @@ impl Runner {
         }
     }
 
+    // levenberg-marquardt on the constants of the best offspring, every
+    // constant_tuning_interval generations
+    fn tune_best_offspring(&mut self) {
+        self.generation += 1;
+        self.tuning_evals = 0;
+        let interval = self.params.constant_tuning_interval;
+        if interval == 0 || self.params.nbr_constants == 0 || !self.generation.is_multiple_of(interval) {
+            return;
+        }
+        let best = (0..self.params.mu + self.params.lambda)
+            .filter(|x| !self.parent_ids.contains(x))
+            .min_by(|a, b| self.fitness_vals[*a].total_cmp(&self.fitness_vals[*b]))
+            .unwrap();
+        let (fitness, evals) = self.chromosomes[best].tune_constants(
+            &self.data,
+            &self.label,
+            &mut self.buffer,
+            self.fitness_vals[best],
+            self.params.constant_tuning_iterations,
+        );
+        self.fitness_vals[best] = fitness;
+        // the buffer holds the last trial, not necessarily the kept constants
+        self.node_outputs_valid[best] = false;
+        self.shared_outputs[best] = None;
+        if self.params.task == TaskKind::Regression {
+            self.mae_vals[best] = self.chromosomes[best].get_mae();
+        }
+        self.best_fitness = get_min(&self.fitness_vals);
+        self.tuning_evals = evals;
+    }
+
+    pub fn get_tuning_evals(&self) -> usize {
+        self.tuning_evals
+    }
+
+    // get_tuning_evals comes on top of both
+    pub fn get_effective_evals(&self) -> usize {
+        self.effective_evals
+    }
+
     fn mutate_chromosomes(&mut self) {
         let mut current_parent: usize = 0;
         // mutate new chromosomes; do not mutate parent
         for i in 0..(self.params.mu + self.params.lambda) {
             if self.parent_ids.contains(&i) {
+                self.origins[i] = None;
                 continue;
             }
 
             self.chromosomes[i] = self.chromosomes[self.parent_ids[current_parent]].clone();
+            self.origins[i] = Some(self.parent_ids[current_parent]);
             current_parent = (current_parent + 1) % self.params.mu;
 
             match self.mutation_type {
                 0 => {
-                    self.chromosomes[i].mutate_single();
+                    self.chromosomes[i].mutate_single(&mut self.rng);
                 }
                 1 => {
-                    self.chromosomes[i].mutate_prob(self.mutation_prob);
+                    self.chromosomes[i].mutate_prob(self.mutation_prob, &mut self.rng);
                 }
 
                 _ => {
@@ -284,16 +479,30 @@ impl Runner {
     }
 
     pub fn evaluate_chromosomes_cgp(&mut self, data:Vec<Vec<f32>>, labels:Vec<Vec<f32>>) -> f32{
-        self.data = data;
+        self.data = evaluator::to_columns(&data);
         self.label = labels;
-        self.eval_chromosomes();
+        self.score_chromosomes();
         let local_best = get_argmin(&self.fitness_vals);
         return self.fitness_vals[local_best];
     }
 
+    // classification report of the chromosome that is best on the given data
+    pub fn confusion_matrix(&mut self, data:Vec<Vec<f32>>, labels:Vec<Vec<f32>>) -> ConfusionMatrix{
+        self.evaluate_chromosomes_cgp(data, labels);
+        let local_best = get_argmin(&self.fitness_vals);
+        let outputs = self.chromosomes[local_best].predict(&self.data, &mut self.buffer);
+        ConfusionMatrix::new(&outputs, &self.label)
+    }
+
+    // the chromosome that is best on the given data, with its linear scaling
+    // kept from training
+    pub fn best_chromosome(&mut self, data:Vec<Vec<f32>>, labels:Vec<Vec<f32>>) -> Chromosome{
+        self.evaluate_chromosomes_cgp(data, labels);
+        let local_best = get_argmin(&self.fitness_vals);
+        self.chromosomes[local_best].clone()
+    }
+
     pub fn best_ant_chromosome(&mut self, conn_pheromone_table:&Vec<Vec<f32>>, func_pheromone_table:&Vec<Vec<f32>>, data:Vec<Vec<f32>>, labels:Vec<Vec<f32>>) -> f32{
-        self.data = data;
-        self.label = labels;
         let connections = calculate_greedy(conn_pheromone_table);
         for i in 0..conn_pheromone_table.len(){
             if self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].node_type != NodeType::OutputNode {
@@ -310,23 +519,20 @@ impl Runner {
         for i in 0..functions.len() {
             self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].function_id = functions[i];
         }
+        self.chromosomes[0].constants = self.ant_constants.clone();
+        // the linear scaling of the greedy chromosome is fitted on the training data
         self.eval_chromosomes();
+        self.data = evaluator::to_columns(&data);
+        self.label = labels;
+        self.score_chromosomes();
         return self.fitness_vals[0];
     }
 
     pub fn best_ant_one_table(&mut self, pheromone_table:&Vec<Vec<f32>>, data:Vec<Vec<f32>>, labels:Vec<Vec<f32>>) -> f32 {
-        self.data = data;
-        self.label = labels;
         let ant = calculate_greedy(pheromone_table);
         for i in 0..ant.len(){
             if i < self.params.graph_width{
-                let mut func_id = ant[i] as usize;
-                if func_id < self.two_conn_constant{
-                    func_id = func_id / self.two_conn_constant;
-                }else{
-                    func_id = func_id - self.two_conn_constant;
-                    func_id = func_id / (self.params.nbr_inputs + self.params.graph_width);
-                }
+                let func_id = self.one_table_function(ant[i]);
                 self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].function_id = func_id;
             }
             if self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].node_type != NodeType::OutputNode {
@@ -347,7 +553,12 @@ impl Runner {
                 self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].connection1 = conn1;
             }
         }
+        self.chromosomes[0].constants = self.ant_constants.clone();
+        // the linear scaling of the greedy chromosome is fitted on the training data
         self.eval_chromosomes();
+        self.data = evaluator::to_columns(&data);
+        self.label = labels;
+        self.score_chromosomes();
         return self.fitness_vals[0];
     }
 
@@ -355,10 +566,9 @@ impl Runner {
         for i in 0..pheromone_table.len(){
             if i < self.params.graph_width{
                 for j in 0..pheromone_table[0].len() {
-                    let current_node = i as f32 + self.params.nbr_inputs as f32;
-                    let conn1 = (j / (self.params.nbr_inputs + self.params.graph_width)) as f32;
-                    let conn2 = (j % (self.params.nbr_inputs + self.params.graph_width)) as f32;
-                    if current_node > conn1 && current_node > conn2 {
+                    let conn1 = j / (self.params.nbr_inputs + self.params.graph_width);
+                    let conn2 = j % (self.params.nbr_inputs + self.params.graph_width);
+                    if self.is_valid_connection(i, conn1) && self.is_valid_connection(i, conn2) {
                         pheromone_table[i][j] = self.params.tau_0;
                     }
                 }
@@ -375,11 +585,11 @@ impl Runner {
     pub fn ant_learn_two_tables(&mut self, pheromone_table_functions: &mut Vec<Vec<f32>>,  pheromone_table_connections: &mut Vec<Vec<f32>>, ant_type:usize) -> f32{
         let func_probs = self.calculate_function_probabilities(pheromone_table_functions, ant_type);
         let mut func_ants: Vec<Vec<i32>> = vec![vec![0; self.params.graph_width];  self.params.mu + self.params.lambda];
-        let mut rng = thread_rng();
-        let func_choices = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+        let func_choices: Vec<i32> = (0..self.params.function_set.len() as i32).collect();
         let conn_probs = self.calculate_connection_probabilities(pheromone_table_connections, ant_type);
         let mut conn_ants: Vec<Vec<i32>> = vec![vec![0; self.params.graph_width + self.params.nbr_outputs];  self.params.mu + self.params.lambda];
         let conn_choices: Vec<i32> = (0..pheromone_table_connections[0].len() as i32).collect();
+        let rng = &mut self.rng;
         if ant_type != 0{
             self.exploration_rate = 1.;
         }
@@ -390,10 +600,10 @@ impl Runner {
                     // exploration
                     if j < self.params.graph_width{
                         let dist = WeightedIndex::new(&func_probs[j]).unwrap();
-                        func_ants[i][j] = func_choices[dist.sample(&mut rng)];
+                        func_ants[i][j] = func_choices[dist.sample(rng)];
                     }
                     let dist = WeightedIndex::new(&conn_probs[j]).unwrap();
-                    conn_ants[i][j] = conn_choices[dist.sample(&mut rng)];
+                    conn_ants[i][j] = conn_choices[dist.sample(rng)];
                 }
                 else{
                     // exploitation
@@ -423,6 +633,7 @@ impl Runner {
             }
         }
 
+        self.sample_ant_constants();
         self.eval_chromosomes();
         self.get_active_nodes();
 
@@ -442,6 +653,7 @@ impl Runner {
         }
 
         let local_best_ant = get_argmin(&self.fitness_vals);
+        self.ant_constants = self.chromosomes[local_best_ant].constants.clone();
         if self.global_best_fitness == -1.{
             self.global_best_fitness = self.fitness_vals[local_best_ant];
             self.func_global_best_ant = func_ants[local_best_ant].clone();
@@ -455,7 +667,8 @@ impl Runner {
                 self.conn_tau_min = (self.conn_tau_max * (1. - self.p_best)) / ((self.avg - 1.) * self.p_best);
 
                 self.func_tau_max = 1. / y * (1. / self.global_best_fitness);
-                self.func_tau_min = (self.func_tau_max * (1. - 1. / 14.)) / ((14. - 1.) * (1. / 14.));
+                let nbr_functions = self.params.function_set.len() as f32;
+                self.func_tau_min = (self.func_tau_max * (1. - 1. / nbr_functions)) / ((nbr_functions - 1.) * (1. / nbr_functions));
             }
         }
         if self.fitness_vals[local_best_ant] < self.global_best_fitness{
@@ -471,7 +684,8 @@ impl Runner {
                 self.conn_tau_min = (self.conn_tau_max * (1. - self.p_best)) / ((self.avg - 1.) * self.p_best);
 
                 self.func_tau_max = 1. / y * (1. / self.global_best_fitness);
-                self.func_tau_min = (self.func_tau_max * (1. - 1. / 14.)) / ((14. - 1.) * (1. / 14.));
+                let nbr_functions = self.params.function_set.len() as f32;
+                self.func_tau_min = (self.func_tau_max * (1. - 1. / nbr_functions)) / ((nbr_functions - 1.) * (1. / nbr_functions));
             }
         }
 
@@ -496,7 +710,7 @@ impl Runner {
 
     pub fn ant_learn_one_table(&mut self, pheromone_table: &mut Vec<Vec<f32>>, ant_type:usize) -> f32 {
         let probs = self.calculate_one_probs(pheromone_table, ant_type);
-        let mut rng = thread_rng();
+        let rng = &mut self.rng;
         let choices: Vec<i32> = (0..pheromone_table[0].len() as i32).collect();
         let mut ants: Vec<Vec<i32>> = vec![vec![0; self.params.graph_width + self.params.nbr_outputs];  self.params.mu + self.params.lambda];
         for i in 0..self.params.mu + self.params.lambda {
@@ -505,7 +719,7 @@ impl Runner {
                 if p < self.exploration_rate {
                     // exploration
                     let dist = WeightedIndex::new(&probs[j]).unwrap();
-                    ants[i][j] = choices[dist.sample(&mut rng)];
+                    ants[i][j] = choices[dist.sample(rng)];
                 }
                 else{
                     // exploitation
@@ -519,13 +733,7 @@ impl Runner {
         for i in 0..self.params.mu + self.params.lambda {
             for j in 0..self.params.graph_width + self.params.nbr_outputs {
                 if j < self.params.graph_width{
-                    let mut func_id = ants[i][j] as usize;
-                    if func_id < self.two_conn_constant{
-                        func_id = func_id / self.two_conn_constant;
-                    }else{
-                        func_id = func_id - self.two_conn_constant;
-                        func_id = func_id / (self.params.nbr_inputs + self.params.graph_width);
-                    }
+                    let func_id = self.one_table_function(ants[i][j] as usize);
                     self.chromosomes[i].nodes_grid[self.params.nbr_inputs + j].function_id = func_id;
                 }
 
@@ -549,6 +757,7 @@ impl Runner {
             }
         }
 
+        self.sample_ant_constants();
         self.eval_chromosomes();
         self.get_active_nodes();
 
@@ -562,6 +771,7 @@ impl Runner {
         }
 
         let local_best_ant = get_argmin(&self.fitness_vals);
+        self.ant_constants = self.chromosomes[local_best_ant].constants.clone();
 
         if self.fitness_vals[local_best_ant] < self.global_best_fitness{
             self.global_best_fitness = self.fitness_vals[local_best_ant];
@@ -583,23 +793,52 @@ impl Runner {
         return self.fitness_vals[local_best_ant];
     }
 
+    // whether computational node i of the pheromone tables may read from connection
+    fn is_valid_connection(&self, i: usize, connection: usize) -> bool {
+        node::is_valid_connection(
+            self.params.nbr_inputs + i,
+            connection,
+            self.params.nbr_inputs,
+            self.params.rows,
+            self.params.levels_back,
+        )
+    }
+
+    // ant 0 keeps the best constants, the others take a gaussian step from them
+    fn sample_ant_constants(&mut self) {
+        for (i, chromosome) in self.chromosomes.iter_mut().enumerate() {
+            chromosome.constants = self.ant_constants.clone();
+            if i > 0 {
+                chromosome.mutate_constants(&mut self.rng);
+            }
+        }
+    }
+
+    // the one-table encoding first lists every (binary function, connection pair)
+    // and then every (unary function, connection), both in function set order
+    fn one_table_function(&self, value: usize) -> usize {
+        let nbr_nodes = self.params.nbr_inputs + self.params.graph_width;
+        if value < self.two_conn_constant {
+            return self.params.function_set.ids_with_arity(2)[value / nbr_nodes.pow(2)];
+        }
+        self.params.function_set.ids_with_arity(1)[(value - self.two_conn_constant) / nbr_nodes]
+    }
+
     pub fn init_one_pheromone_table(&mut self, pheromone_table: &mut Vec<Vec<f32>>){
         for i in 0..pheromone_table.len(){
             if i < self.params.graph_width{
                 for j in 0..pheromone_table[0].len() {
-                    let current_node = i as f32 + self.params.nbr_inputs as f32;
                     if j < self.two_conn_constant{
                         let adjusted_j = j % (self.params.nbr_inputs + self.params.graph_width).pow(2);
-                        let conn1 = (adjusted_j / (self.params.nbr_inputs + self.params.graph_width)) as f32;
-                        let conn2 = (adjusted_j % (self.params.nbr_inputs + self.params.graph_width)) as f32;
-                        if current_node > conn1 && current_node > conn2 {
+                        let conn1 = adjusted_j / (self.params.nbr_inputs + self.params.graph_width);
+                        let conn2 = adjusted_j % (self.params.nbr_inputs + self.params.graph_width);
+                        if self.is_valid_connection(i, conn1) && self.is_valid_connection(i, conn2) {
                             pheromone_table[i][j] = self.params.tau_0;
                         }
                     }
                     else{
-                        let mut adjusted_j = j as f32 - self.two_conn_constant as f32;
-                        adjusted_j = adjusted_j % (self.params.nbr_inputs + self.params.graph_width) as f32;
-                        if current_node > adjusted_j{
+                        let adjusted_j = (j - self.two_conn_constant) % (self.params.nbr_inputs + self.params.graph_width);
+                        if self.is_valid_connection(i, adjusted_j) {
                             pheromone_table[i][j] = self.params.tau_0;
                         }
                     }
@@ -736,11 +975,12 @@ impl Runner {
     pub fn initialize_mmas(&mut self, pheromone_table:&mut Vec<Vec<f32>>){
         for i in 0..pheromone_table.len(){
             if self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].node_type != NodeType::OutputNode {
-                if self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].function_id < 4{
-                    let num_poss:f32 = ((self.params.nbr_inputs + i) * (self.params.nbr_inputs + i)) as f32;
+                let nbr_candidates = self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].connection_range().len();
+                if self.params.function_set.arity(self.chromosomes[0].nodes_grid[self.params.nbr_inputs + i].function_id) == 2{
+                    let num_poss:f32 = (nbr_candidates * nbr_candidates) as f32;
                     self.avg = self.avg + num_poss;
                 }else{
-                    let num_poss:f32 = (self.params.nbr_inputs + i) as f32;
+                    let num_poss:f32 = nbr_candidates as f32;
                     self.avg = self.avg + num_poss;
                 }
             } else{
@@ -913,19 +1153,115 @@ impl Runner {
 
 
     fn eval_chromosomes(&mut self) {
+        self.evaluate_population(true);
+    }
+
+    // like eval_chromosomes, but keeps the linear scaling fitted on the training data
+    fn score_chromosomes(&mut self) {
+        self.evaluate_population(false);
+    }
+
+    // (mu + lambda) step: the offspring reuse the node outputs of their parent,
+    // see Chromosome::evaluate_offspring, and the parents keep their fitness.
+    // Parents without valid node outputs are evaluated from scratch first. The
+    // chromosomes are evaluated in parallel, the rng is not used here
+    fn eval_offspring(&mut self) {
+        // before the origin of a parent is overwritten by its own offspring
         for i in 0..(self.params.mu + self.params.lambda) {
-            let mut fitness = self.chromosomes[i].evaluate(&self.data, &self.label);
-            if self.label[0].len() == 1{
-                self.mae_vals[i] = self.chromosomes[i].get_mae();
+            if let (None, Some(source)) = (self.origins[i], self.shared_outputs[i]) {
+                if self.node_outputs_valid[source] {
+                    self.node_outputs[i] = self.node_outputs[source].clone();
+                    self.node_outputs_valid[i] = true;
+                }
             }
-            if fitness.is_nan(){
-                fitness = f32::INFINITY;
+        }
+        self.shared_outputs.fill(None);
+
+        let outdated: Vec<bool> = (0..(self.params.mu + self.params.lambda))
+            .map(|i| self.origins[i].is_none() && !self.node_outputs_valid[i])
+            .collect();
+        let fitnesses: Vec<Option<f32>> = self.chromosomes
+            .par_iter_mut()
+            .zip(self.node_outputs.par_iter_mut())
+            .zip(&outdated)
+            .map(|((chromosome, buffer), outdated)| outdated.then(|| chromosome.evaluate(&self.data, &self.label, buffer)))
+            .collect();
+        for (i, fitness) in fitnesses.into_iter().enumerate() {
+            if let Some(fitness) = fitness {
+                self.set_fitness(i, fitness);
+                self.node_outputs_valid[i] = true;
             }
-            self.fitness_vals[i] = fitness;
         }
-        let best_fitness = get_min(&self.fitness_vals);
 
-        self.best_fitness = best_fitness;
+        // the parents are read by all offspring while those are written
+        let parents: Vec<(usize, Chromosome, EvaluationBuffer)> = (0..(self.params.mu + self.params.lambda))
+            .filter(|i| self.origins[*i].is_none())
+            .map(|i| (i, self.chromosomes[i].clone(), std::mem::take(&mut self.node_outputs[i])))
+            .collect();
+        let fitnesses: Vec<Option<Option<f32>>> = self.chromosomes
+            .par_iter_mut()
+            .zip(self.node_outputs.par_iter_mut())
+            .zip(&self.origins)
+            .map(|((chromosome, buffer), origin)| {
+                origin.map(|origin| {
+                    let (_, parent, parent_buffer) = parents.iter().find(|x| x.0 == origin).unwrap();
+                    chromosome.evaluate_offspring(parent, parent_buffer, &self.data, &self.label, buffer)
+                })
+            })
+            .collect();
+        for (i, _, buffer) in parents {
+            self.node_outputs[i] = buffer;
+        }
+
+        self.effective_evals = 0;
+        self.node_outputs_valid.fill(true);
+        for (i, fitness) in fitnesses.into_iter().enumerate() {
+            match (fitness, self.origins[i]) {
+                (Some(Some(fitness)), _) => {
+                    self.effective_evals += 1;
+                    self.set_fitness(i, fitness);
+                }
+                (Some(None), Some(origin)) => {
+                    self.set_fitness(i, self.fitness_vals[origin]);
+                    self.node_outputs_valid[i] = false;
+                    self.shared_outputs[i] = Some(origin);
+                }
+                _ => {}
+            }
+        }
+        self.best_fitness = get_min(&self.fitness_vals);
+    }
+
+    // every chromosome from scratch, in parallel
+    fn evaluate_population(&mut self, training: bool) {
+        // the chromosomes or the data may have changed
+        self.node_outputs_valid.fill(false);
+        self.shared_outputs.fill(None);
+        let (data, label) = (&self.data, &self.label);
+        let fitnesses: Vec<f32> = self.chromosomes
+            .par_iter_mut()
+            .map_init(EvaluationBuffer::new, |buffer, chromosome| {
+                if training {
+                    chromosome.evaluate(data, label, buffer)
+                } else {
+                    chromosome.score(data, label, buffer)
+                }
+            })
+            .collect();
+        for (i, fitness) in fitnesses.into_iter().enumerate() {
+            self.set_fitness(i, fitness);
+        }
+        self.best_fitness = get_min(&self.fitness_vals);
+    }
+
+    fn set_fitness(&mut self, i: usize, mut fitness: f32) {
+        if self.params.task == TaskKind::Regression{
+            self.mae_vals[i] = self.chromosomes[i].get_mae();
+        }
+        if fitness.is_nan(){
+            fitness = f32::INFINITY;
+        }
+        self.fitness_vals[i] = fitness;
     }
 
     pub fn get_best_fitness(&self) -> f32 {
@@ -951,6 +1287,12 @@ impl Runner {
         return self.fitness_vals.clone();
     }
 
+    // per-output errors of the currently best chromosome
+    pub fn get_best_output_errors(&self) -> Vec<f32> {
+        let index = get_argmin(&self.fitness_vals);
+        self.chromosomes[index].get_output_errors()
+    }
+
     pub fn get_maes(&self) -> Vec<f32>{
         return self.mae_vals.clone();
     }
@@ -965,7 +1307,7 @@ impl Runner {
         return  sum / self.mae_vals.len() as f32;
     }
     pub fn calculate_function_probabilities(&self, pheromone_table: &Vec<Vec<f32>>, ant_type:usize) -> Vec<Vec<f32>>{
-        let mut probs :Vec<Vec<f32>>= vec![vec![0.0; 14];  pheromone_table.len()];
+        let mut probs :Vec<Vec<f32>>= vec![vec![0.0; pheromone_table[0].len()];  pheromone_table.len()];
         for i in 0..pheromone_table.len() {
             for j in 0..pheromone_table[0].len(){
                 if ant_type == 0{
@@ -1,17 +1,23 @@
 use crate::global_params::CgpParameters as g_params;
 use crate::cgp_es::chromosome::Chromosome;
+use crate::cgp_es::evaluator::{self, EvaluationBuffer};
+use crate::utils::random::CgpRng;
 use float_eq::float_eq;
+use ndarray::Array2;
 use rand::seq::SliceRandom;
 use std::fmt::{Display, Formatter};
 
 pub struct Runner {
     params: g_params,
-    data: Vec<Vec<f32>>,
+    // column-major, see evaluator::to_columns
+    data: Array2<f32>,
     label: Vec<Vec<f32>>,
+    buffer: EvaluationBuffer,
     chromosomes: Vec<Chromosome>,
     fitness_vals_sorted: Vec<f32>,
     fitness_vals: Vec<f32>,
     parent_ids: Vec<usize>,
+    rng: CgpRng,
 }
 
 impl Display for Runner {
@@ -24,13 +30,15 @@ impl Display for Runner {
 }
 
 impl Runner {
-    pub fn new(params: g_params, data: Vec<Vec<f32>>, label: Vec<Vec<f32>>) -> Self {
+    pub fn new(params: g_params, data: Vec<Vec<f32>>, label: Vec<Vec<f32>>, mut rng: CgpRng) -> Self {
         let mut chromosomes: Vec<Chromosome> = Vec::with_capacity(params.mu + params.lambda);
         let mut fitness_vals: Vec<f32> = Vec::with_capacity(params.mu + params.lambda);
+        let data = evaluator::to_columns(&data);
+        let mut buffer = EvaluationBuffer::new();
 
         for _ in 0..(params.mu + params.lambda) {
-            let mut chromosome = Chromosome::new(params.clone());
-            let fitness = chromosome.evaluate(&data, &label);
+            let mut chromosome = Chromosome::new(params.clone(), &mut rng);
+            let fitness = chromosome.evaluate(&data, &label, &mut buffer);
             fitness_vals.push(fitness);
 
             chromosomes.push(chromosome);
@@ -64,10 +72,12 @@ impl Runner {
             params,
             data,
             label,
+            buffer,
             chromosomes,
             fitness_vals,
             fitness_vals_sorted,
             parent_ids,
+            rng,
         }
     }
 
@@ -138,9 +148,9 @@ impl Runner {
                 continue;
             }
 
-            let rand_parent_id = self.parent_ids.choose(&mut rand::thread_rng()).unwrap();
+            let rand_parent_id = self.parent_ids.choose(&mut self.rng).unwrap();
             self.chromosomes[i] = self.chromosomes[*rand_parent_id].clone();
-            self.chromosomes[i].mutate_single();
+            self.chromosomes[i].mutate_single(&mut self.rng);
             assert_ne!(i, *rand_parent_id);
         }
     }
@@ -148,7 +158,7 @@ impl Runner {
     fn eval_chromosomes(&mut self) {
         for i in 0..(self.params.mu + self.params.lambda) {
             if !self.parent_ids.contains(&i) {
-                let fitness = self.chromosomes[i].evaluate(&self.data, &self.label);
+                let fitness = self.chromosomes[i].evaluate(&self.data, &self.label, &mut self.buffer);
                 self.fitness_vals[i] = fitness;
 
                 // // TODO CHECK HERE
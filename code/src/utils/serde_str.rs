@@ -0,0 +1,21 @@
+use serde::{de, Deserialize, Deserializer, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
+
+// #[serde(with = "crate::utils::serde_str")] writes a value through Display and
+// reads it back through FromStr, so that e.g. a metric is stored as "huber:1.5"
+// like on the command line and function sets are stored by name
+
+pub fn serialize<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+}
@@ -0,0 +1,107 @@
+use cgp::datasets::arff::{self, AttributeType};
+use cgp::datasets::loader::DatasetError;
+use std::fs;
+use std::path::PathBuf;
+
+const FIXTURE: &str = "% a comment before the header
+@RELATION 'weather test'
+
+@ATTRIBUTE outlook {sunny, 'over cast', rainy}
+@attribute 'air temp' REAL
+@attribute humidity numeric
+@attribute note string
+@attribute play {yes,no}
+
+@DATA
+% a comment inside the data
+sunny, 85, 85, 'it\\'s fine', no
+'over cast',?,86,\"a, b\",yes
+rainy,  70.5 ,96, ?, yes
+";
+
+fn write_fixture(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("cgp_{}_{}.arff", name, std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn header_quotes_comments_and_missing_values() {
+    let path = write_fixture("parse", FIXTURE);
+    let parsed = arff::parse(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(parsed.relation, "weather test");
+    let names: Vec<&str> = parsed.attributes.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(names, ["outlook", "air temp", "humidity", "note", "play"]);
+    assert_eq!(
+        parsed.attributes[0].kind,
+        AttributeType::Nominal(vec!["sunny".to_string(), "over cast".to_string(), "rainy".to_string()])
+    );
+    assert_eq!(parsed.attributes[1].kind, AttributeType::Numeric);
+    assert_eq!(parsed.attributes[3].kind, AttributeType::String);
+    assert_eq!(parsed.row_lines, [12, 13, 14]);
+    assert_eq!(parsed.rows[1][0].as_deref(), Some("over cast"));
+    assert_eq!(parsed.rows[1][1], None);
+    // escapes and commas inside quotes stay part of the field
+    assert_eq!(parsed.rows[0][3].as_deref(), Some("it's fine"));
+    assert_eq!(parsed.rows[1][3].as_deref(), Some("a, b"));
+    assert_eq!(parsed.rows[2][3], None);
+}
+
+#[test]
+fn load_encodes_features_and_one_hot_classes() {
+    let path = write_fixture("load", FIXTURE);
+    let (data, labels) = arff::load(&path, None).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    // the string attribute is skipped, nominal values become their index
+    assert_eq!(data[0], [0., 85., 85.]);
+    assert_eq!(data[1][0], 1.);
+    assert!(data[1][1].is_nan());
+    assert_eq!(data[2], [2., 70.5, 96.]);
+    assert_eq!(labels, [vec![0., 1.], vec![1., 0.], vec![1., 0.]]);
+}
+
+#[test]
+fn wrong_field_count_is_reported_with_its_line() {
+    let path = write_fixture("fields", "@relation r\n@attribute a numeric\n@attribute c {x,y}\n@data\n1,x\n2\n");
+    let result = arff::parse(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert!(matches!(result, Err(DatasetError::Malformed { line: 6, .. })));
+}
+
+#[test]
+fn misspelled_and_numeric_class_attributes_are_told_apart() {
+    let path = write_fixture("class", FIXTURE);
+    let misspelled = arff::load(&path, Some("plays"));
+    let numeric = arff::load(&path, Some("humidity"));
+    fs::remove_file(&path).unwrap();
+
+    assert!(matches!(misspelled, Err(DatasetError::UnknownAttribute { ref name, .. }) if name == "plays"));
+    assert!(matches!(numeric, Err(DatasetError::ClassAttribute { ref name, .. }) if name == "humidity"));
+}
+
+#[test]
+fn malformed_headers_are_reported_with_their_line() {
+    let cases = [
+        ("@relation r\n@atribute a numeric\n@data\n1\n", 2, "unexpected '@atribute'"),
+        ("@relation r\n@attribute a numeric\n@attribute c {x,y\n@data\n1,x\n", 3, "unterminated nominal"),
+        ("@relation r\n% no type\n@attribute a\n@data\n1\n", 3, "needs a name and a type"),
+        ("@relation r\n@attribute a relational\n@data\n1\n", 2, "unsupported attribute type 'relational'"),
+        ("@relation r\n@data\n1\n", 2, "@data before any @attribute"),
+    ];
+    for (i, (contents, expected_line, expected_message)) in cases.into_iter().enumerate() {
+        let path = write_fixture(&format!("header_{}", i), contents);
+        let result = arff::parse(&path);
+        fs::remove_file(&path).unwrap();
+        match result {
+            Err(DatasetError::Malformed { line, message, .. }) => {
+                assert_eq!(line, expected_line, "{}", contents);
+                assert!(message.contains(expected_message), "{}", message);
+            }
+            _ => panic!("{} was accepted", contents),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use cgp::datasets::boolean::{self, BooleanSamples};
+
+fn bits(row: &str) -> Vec<bool> {
+    row.chars().map(|x| x == '1').collect()
+}
+
+fn column(samples: &BooleanSamples, column: usize) -> String {
+    samples.1.column(column).iter().map(|&x| if x { '1' } else { '0' }).collect()
+}
+
+#[test]
+fn multiplexer_2_selects_the_addressed_data_bit() {
+    let samples = boolean::multiplexer(2);
+    assert_eq!(samples.0.dim(), (64, 6));
+    assert_eq!(samples.1.dim(), (64, 1));
+    // row 0b01_0110: address 1 selects d1, the second data column
+    assert_eq!(samples.0.row(22).to_vec(), bits("010110"));
+    // 16 rows per address a1 a0, the data bits d0 d1 d2 d3 count up within them
+    let expected = [
+        "0000000011111111", // address 00: d0
+        "0000111100001111", // address 01: d1
+        "0011001100110011", // address 10: d2
+        "0101010101010101", // address 11: d3
+    ];
+    assert_eq!(column(&samples, 0), expected.concat());
+}
+
+#[test]
+fn adder_2_outputs_the_three_bit_sum() {
+    let samples = boolean::adder(2);
+    assert_eq!(samples.0.dim(), (16, 4));
+    // a1 a0 b1 b0 -> s2 s1 s0, rows in the order of a1 a0 b1 b0 as a number
+    let table = [
+        ("0000", "000"), ("0001", "001"), ("0010", "010"), ("0011", "011"),
+        ("0100", "001"), ("0101", "010"), ("0110", "011"), ("0111", "100"),
+        ("1000", "010"), ("1001", "011"), ("1010", "100"), ("1011", "101"),
+        ("1100", "011"), ("1101", "100"), ("1110", "101"), ("1111", "110"),
+    ];
+    for (row, (inputs, sum)) in table.iter().enumerate() {
+        assert_eq!(samples.0.row(row).to_vec(), bits(inputs), "row {}", row);
+        assert_eq!(samples.1.row(row).to_vec(), bits(sum), "row {}", row);
+    }
+}
+
+#[test]
+fn find_rejects_problems_that_are_too_large() {
+    assert!(boolean::find("majority-9").is_some());
+    assert!(boolean::find("multiplexer-4").is_some());
+    for spec in ["parity-21", "parity-64", "multiplexer-5", "multiplexer-64", "adder-11", "comparator-99999999999999999"] {
+        assert!(boolean::find(spec).is_none(), "{}", spec);
+    }
+}
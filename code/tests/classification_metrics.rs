@@ -0,0 +1,117 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::evaluator::{self, EvaluationBuffer};
+use cgp::datasets::dataset::TaskKind;
+use cgp::global_params::CgpParameters;
+use cgp::utils::classification_metrics::ConfusionMatrix;
+use cgp::utils::fitness_metrics::{self, ClassificationObjective};
+use cgp::utils::random::CgpRng;
+use float_eq::assert_float_eq;
+use rand::SeedableRng;
+
+fn one_hot(classes: &[usize], nbr_classes: usize) -> Vec<Vec<f32>> {
+    classes
+        .iter()
+        .map(|&class| (0..nbr_classes).map(|k| if k == class { 1. } else { 0. }).collect())
+        .collect()
+}
+
+// true classes 0 0 1 1 2 2, predicted 0 1 1 1 2 0:
+// R_K = (4 * 6 - 12) / (sqrt(36 - 14) * sqrt(36 - 12)) = 12 / sqrt(528)
+fn three_class_case() -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
+    (one_hot(&[0, 1, 1, 1, 2, 0], 3), one_hot(&[0, 0, 1, 1, 2, 2], 3))
+}
+
+#[test]
+fn multiclass_mcc_uses_true_label_counts() {
+    let (outputs, labels) = three_class_case();
+    let expected = 12. / 528_f32.sqrt();
+
+    assert_float_eq!(ConfusionMatrix::new(&outputs, &labels).mcc(), expected, abs <= 1e-6);
+
+    let params = CgpParameters {
+        nbr_inputs: 1,
+        graph_width: 1,
+        nbr_outputs: 3,
+        ..Default::default()
+    };
+    let mut chromosome = Chromosome::new(params, &mut CgpRng::seed_from_u64(0));
+    // the fitness is 1 - |mcc|
+    assert_float_eq!(chromosome.mcc(&outputs, &labels), 1. - expected, abs <= 1e-6);
+}
+
+#[test]
+fn binary_mcc_matches_the_textbook_formula() {
+    // tp = 2, tn = 1, fp = 1, fn = 1
+    let outputs = one_hot(&[1, 0, 0, 1, 1], 2);
+    let labels = one_hot(&[1, 1, 0, 0, 1], 2);
+    let expected = (2. * 1. - 1. * 1.) / (3_f32 * 3. * 2. * 2.).sqrt();
+    assert_float_eq!(ConfusionMatrix::new(&outputs, &labels).mcc(), expected, abs <= 1e-6);
+}
+
+#[test]
+fn report_metrics() {
+    let (outputs, labels) = three_class_case();
+    let matrix = ConfusionMatrix::new(&outputs, &labels);
+
+    assert_eq!(matrix.counts, vec![vec![1, 1, 0], vec![0, 2, 0], vec![1, 0, 1]]);
+    assert_float_eq!(matrix.accuracy(), 4. / 6., abs <= 1e-6);
+    assert_float_eq!(matrix.micro_f1(), 4. / 6., abs <= 1e-6);
+    assert_float_eq!(matrix.balanced_accuracy(), (0.5 + 1. + 0.5) / 3., abs <= 1e-6);
+    assert_float_eq!(matrix.precision(1), 2. / 3., abs <= 1e-6);
+    assert_float_eq!(matrix.recall(0), 0.5, abs <= 1e-6);
+    // f1 per class: 0.5, 0.8, 2/3
+    assert_float_eq!(matrix.macro_f1(), (0.5 + 0.8 + 2. / 3.) / 3., abs <= 1e-6);
+    // p_o = 4/6, p_e = (2*2 + 2*3 + 2*1) / 36 = 1/3
+    assert_float_eq!(matrix.cohens_kappa(), 0.5, abs <= 1e-6);
+}
+
+#[test]
+fn softmax_objectives_of_hand_computed_probabilities() {
+    // probabilities 1/4 and 3/4
+    let output = [0., 3_f32.ln()];
+    assert_float_eq!(fitness_metrics::softmax(&output), vec![0.25, 0.75], abs_all <= 1e-6);
+    assert_eq!(fitness_metrics::softmax(&[1000., 1000.]), [0.5, 0.5]);
+
+    assert_float_eq!(fitness_metrics::cross_entropy_case(&output, &[0., 1.]), -0.75_f32.ln(), abs <= 1e-6);
+    assert_float_eq!(fitness_metrics::cross_entropy_case(&output, &[1., 0.]), 4_f32.ln(), abs <= 1e-6);
+    // a confidently wrong output is clamped to p = 1e-7
+    assert_float_eq!(fitness_metrics::cross_entropy_case(&[0., 200.], &[1., 0.]), -1e-7_f32.ln(), abs <= 1e-4);
+
+    assert_float_eq!(fitness_metrics::brier_case(&output, &[0., 1.]), 0.125, abs <= 1e-6);
+    assert_float_eq!(fitness_metrics::brier_case(&output, &[1., 0.]), 1.125, abs <= 1e-6);
+    assert_float_eq!(fitness_metrics::brier_case(&[0., 200.], &[1., 0.]), 2., abs <= 1e-6);
+
+    let outputs = vec![output.to_vec(), output.to_vec()];
+    let labels = one_hot(&[1, 0], 2);
+    assert_float_eq!(fitness_metrics::cross_entropy(&outputs, &labels), (4_f32.ln() - 0.75_f32.ln()) / 2., abs <= 1e-6);
+    assert_float_eq!(fitness_metrics::brier_score(&outputs, &labels), 0.625, abs <= 1e-6);
+}
+
+#[test]
+fn the_classification_objective_is_the_fitness() {
+    // the two outputs read the two inputs, so the data rows are the outputs
+    let data = vec![vec![0., 3_f32.ln()], vec![2., -1.], vec![0.5, 0.5]];
+    let labels = one_hot(&[1, 0, 1], 2);
+    let objectives = [
+        (ClassificationObjective::CrossEntropy, fitness_metrics::cross_entropy(&data, &labels)),
+        (ClassificationObjective::Brier, fitness_metrics::brier_score(&data, &labels)),
+        // all three argmax outputs are right
+        (ClassificationObjective::Mcc, 0.),
+    ];
+    for (objective, expected) in objectives {
+        assert_eq!(objective.to_string().parse::<ClassificationObjective>(), Ok(objective));
+        let params = CgpParameters {
+            nbr_inputs: 2,
+            graph_width: 1,
+            nbr_outputs: 2,
+            task: TaskKind::Classification,
+            classification_objective: objective,
+            ..Default::default()
+        };
+        let mut chromosome = Chromosome::new(params, &mut CgpRng::seed_from_u64(0));
+        chromosome.nodes_grid[3].connection1 = 0;
+        chromosome.nodes_grid[4].connection1 = 1;
+        let fitness = chromosome.evaluate(&evaluator::to_columns(&data), &labels, &mut EvaluationBuffer::new());
+        assert_float_eq!(fitness, expected, abs <= 1e-6, "{}", objective);
+    }
+}
@@ -0,0 +1,81 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::evaluator::{self, EvaluationBuffer};
+use cgp::datasets::dataset::TaskKind;
+use cgp::global_params::CgpParameters;
+use cgp::utils::random::CgpRng;
+use rand::SeedableRng;
+
+// x0 * c with c starting at 1: node 2 multiplies the input and the constant,
+// the output node 4 reads it
+fn scaled_input() -> Chromosome {
+    let params = CgpParameters {
+        nbr_inputs: 2,
+        nbr_constants: 1,
+        graph_width: 2,
+        nbr_outputs: 1,
+        function_set: "mul,add".parse().unwrap(),
+        ..Default::default()
+    };
+    let mut chromosome = Chromosome::new(params, &mut CgpRng::seed_from_u64(0));
+    chromosome.constants = vec![1.];
+    let node = &mut chromosome.nodes_grid[2];
+    (node.function_id, node.connection1, node.connection2) = (0, 0, 1);
+    chromosome.nodes_grid[4].connection1 = 2;
+    chromosome
+}
+
+#[test]
+fn tuning_recovers_a_known_constant() {
+    let data: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32 / 4. - 2.]).collect();
+    let labels: Vec<Vec<f32>> = data.iter().map(|x| vec![2.5 * x[0]]).collect();
+    let inputs = evaluator::to_columns(&data);
+    let mut buffer = EvaluationBuffer::new();
+
+    let mut chromosome = scaled_input();
+    let fitness = chromosome.evaluate(&inputs, &labels, &mut buffer);
+    assert!(fitness > 1.);
+    let (tuned, evals) = chromosome.tune_constants(&inputs, &labels, &mut buffer, fitness, 20);
+    assert!((chromosome.constants[0] - 2.5).abs() < 1e-4, "{}", chromosome.constants[0]);
+    assert!(tuned < 1e-6, "{}", tuned);
+    assert!(evals > 1);
+}
+
+#[test]
+fn tuning_through_pow_at_zero_is_not_aborted() {
+    // |x0 + c|^x1 with c starting at 1: the base is 0 at x0 = -1, which used
+    // to give a NaN partial that stopped the tuning
+    let params = CgpParameters {
+        nbr_inputs: 3,
+        nbr_constants: 1,
+        graph_width: 2,
+        nbr_outputs: 1,
+        function_set: "add,pow".parse().unwrap(),
+        ..Default::default()
+    };
+    let mut chromosome = Chromosome::new(params, &mut CgpRng::seed_from_u64(0));
+    chromosome.constants = vec![1.];
+    let node = &mut chromosome.nodes_grid[3];
+    (node.function_id, node.connection1, node.connection2) = (0, 0, 2);
+    let node = &mut chromosome.nodes_grid[4];
+    (node.function_id, node.connection1, node.connection2) = (1, 3, 1);
+    chromosome.nodes_grid[5].connection1 = 4;
+
+    let data: Vec<Vec<f32>> = (0..21).map(|i| vec![i as f32 / 10. - 1., 0.5]).collect();
+    let labels: Vec<Vec<f32>> = data.iter().map(|x| vec![(x[0] + 1.5).sqrt()]).collect();
+    let inputs = evaluator::to_columns(&data);
+    let mut buffer = EvaluationBuffer::new();
+    let fitness = chromosome.evaluate(&inputs, &labels, &mut buffer);
+    chromosome.tune_constants(&inputs, &labels, &mut buffer, fitness, 20);
+    assert!((chromosome.constants[0] - 1.5).abs() < 1e-3, "{}", chromosome.constants[0]);
+}
+
+#[test]
+fn classification_constants_are_not_tuned() {
+    let mut chromosome = scaled_input();
+    chromosome.params.task = TaskKind::Classification;
+    let data: Vec<Vec<f32>> = (0..10).map(|i| vec![i as f32 - 5.]).collect();
+    let labels: Vec<Vec<f32>> = data.iter().map(|x| vec![(x[0] > 0.) as u8 as f32]).collect();
+    let (fitness, evals) = chromosome.tune_constants(&evaluator::to_columns(&data), &labels, &mut EvaluationBuffer::new(), 0.5, 20);
+    assert_eq!((fitness, evals), (0.5, 0));
+    assert_eq!(chromosome.constants, [1.]);
+}
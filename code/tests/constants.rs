@@ -0,0 +1,69 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::evaluator::{self, EvaluationBuffer};
+use cgp::global_params::CgpParameters;
+use cgp::utils::random::CgpRng;
+use rand::SeedableRng;
+
+// one data input x0 and the constant inputs 1 and 2
+fn params() -> CgpParameters {
+    CgpParameters {
+        nbr_inputs: 3,
+        nbr_constants: 2,
+        constant_range: 0.5,
+        constant_sigma: 0.2,
+        graph_width: 10,
+        nbr_outputs: 1,
+        function_set: "add,mul".parse().unwrap(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn constants_are_drawn_from_their_range() {
+    for seed in 0..20 {
+        let chromosome = Chromosome::new(params(), &mut CgpRng::seed_from_u64(seed));
+        assert_eq!(chromosome.constants.len(), 2);
+        assert!(chromosome.constants.iter().all(|x| x.abs() <= 0.5), "{:?}", chromosome.constants);
+    }
+}
+
+#[test]
+fn constant_inputs_carry_their_value_into_predictions() {
+    let mut chromosome = Chromosome::new(params(), &mut CgpRng::seed_from_u64(0));
+    chromosome.constants = vec![0.25, -3.];
+    // node 3 = x0 * c1, the output reads it
+    let node = &mut chromosome.nodes_grid[3];
+    (node.function_id, node.connection1, node.connection2) = (1, 0, 2);
+    chromosome.nodes_grid[13].connection1 = 3;
+    let inputs = evaluator::to_columns(&[vec![1.], vec![-2.]]);
+    let mut buffer = EvaluationBuffer::new();
+    assert_eq!(chromosome.predict(&inputs, &mut buffer), [[-3.], [6.]]);
+    assert_eq!(chromosome.expression(0), "(-3 * x0)");
+    let printed = chromosome.to_string();
+    assert!(printed.contains("Constants: [0.25, -3.0]"), "{}", printed);
+
+    chromosome.constants[1] = 2.;
+    assert_eq!(chromosome.predict(&inputs, &mut buffer), [[2.], [-4.]]);
+}
+
+#[test]
+fn mutation_takes_gaussian_steps_on_the_constants_only() {
+    let mut rng = CgpRng::seed_from_u64(3);
+    let mut chromosome = Chromosome::new(params(), &mut rng);
+    let nodes: Vec<(usize, usize, usize)> =
+        chromosome.nodes_grid.iter().map(|x| (x.function_id, x.connection1, x.connection2)).collect();
+    let mut steps = Vec::new();
+    for _ in 0..2000 {
+        let before = chromosome.constants.clone();
+        chromosome.mutate_constants(&mut rng);
+        steps.extend(chromosome.constants.iter().zip(&before).map(|(a, b)| a - b));
+    }
+    let after: Vec<(usize, usize, usize)> =
+        chromosome.nodes_grid.iter().map(|x| (x.function_id, x.connection1, x.connection2)).collect();
+    assert_eq!(after, nodes);
+
+    let mean = steps.iter().sum::<f32>() / steps.len() as f32;
+    let deviation = (steps.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / steps.len() as f32).sqrt();
+    assert!(mean.abs() < 0.02, "{}", mean);
+    assert!((deviation - 0.2).abs() < 0.01, "{}", deviation);
+}
@@ -0,0 +1,58 @@
+use cgp::utils::fitness_metrics::{FitnessMetric, RegressionMetric};
+
+const LABELS: [f32; 5] = [1., 2., 3., 4., 5.];
+// absolute errors 0.5 0 0 0 0.5
+const OUTPUTS: [f32; 5] = [1.5, 2., 3., 4., 4.5];
+
+fn metric(name: &str) -> RegressionMetric {
+    name.parse().unwrap()
+}
+
+fn assert_close(value: f32, expected: f32) {
+    assert!((value - expected).abs() < 1e-6, "{} != {}", value, expected);
+}
+
+#[test]
+fn squared_and_absolute_errors() {
+    assert_close(metric("mse").evaluate(&OUTPUTS, &LABELS), 0.1);
+    assert_close(metric("rmse").evaluate(&OUTPUTS, &LABELS), 0.1f32.sqrt());
+    assert_close(metric("mae").evaluate(&OUTPUTS, &LABELS), 0.2);
+    // the variance of the labels is 2
+    assert_close(metric("nmse").evaluate(&OUTPUTS, &LABELS), 0.05);
+}
+
+#[test]
+fn huber_is_quadratic_up_to_delta_and_linear_above() {
+    // 0.5 * 0.5² per error of 0.5
+    assert_close(metric("huber").evaluate(&OUTPUTS, &LABELS), 0.05);
+    // errors 0.5 and 3 with delta 1: 0.125 and 1 * (3 - 0.5)
+    assert_close(metric("huber:1").evaluate(&[0.5, 3.], &[0., 0.]), (0.125 + 2.5) / 2.);
+    // both sides agree at delta
+    assert_close(metric("huber:2").evaluate(&[2.], &[0.]), 2.);
+    assert!("huber:0".parse::<RegressionMetric>().is_err());
+}
+
+#[test]
+fn median_absolute_error() {
+    assert_eq!(metric("medae").evaluate(&OUTPUTS, &LABELS), 0.);
+    // even count: mean of the middle errors 2 and 3
+    assert_eq!(metric("medae").evaluate(&[1., 3., 2., 10.], &[0.; 4]), 2.5);
+    assert!(metric("medae").evaluate(&[f32::NAN, 1.], &[0., 0.]).is_nan());
+}
+
+#[test]
+fn r2_minimises_one_minus_r2_and_reports_r2() {
+    let r2 = metric("r2");
+    assert_eq!(r2, RegressionMetric::RSquared);
+    assert_eq!(r2.to_string(), "r2");
+    assert_eq!(r2.to_string().parse::<RegressionMetric>().unwrap(), r2);
+
+    // SS_res = 0.5 and SS_tot = 10
+    let fitness = r2.evaluate(&OUTPUTS, &LABELS);
+    assert_close(fitness, 0.05);
+    assert_close(r2.report(fitness), 0.95);
+    assert_eq!(metric("mse").report(fitness), fitness);
+    // constant labels: only an exact fit has a defined R²
+    assert_eq!(r2.evaluate(&[3., 3.], &[3., 3.]), 0.);
+    assert_eq!(r2.evaluate(&[3., 4.], &[3., 3.]), f32::INFINITY);
+}
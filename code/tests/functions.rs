@@ -0,0 +1,105 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::dual::Dual;
+use cgp::cgp_es::functions::{FunctionSet, FUNCTIONS};
+use cgp::cgp_es::node::{Node, ROW_CHUNK_SIZE};
+use cgp::global_params::CgpParameters;
+use cgp::utils::node_type::NodeType;
+use cgp::utils::random::CgpRng;
+use rand::{Rng, SeedableRng};
+
+// away from the kinks of relu, abs, min and max and the poles of tan and div
+const POINTS: &[(f32, f32)] = &[(0.7, 1.3), (-0.4, 0.9), (1.9, -0.6), (-1.2, -2.1)];
+const STEP: f32 = 1e-3;
+
+#[test]
+fn partials_match_central_differences() {
+    let set = FunctionSet { functions: FUNCTIONS.to_vec() };
+    for (id, function) in FUNCTIONS.iter().enumerate() {
+        for &(a, b) in POINTS {
+            let expected_a = (set.apply(id, a + STEP, b) - set.apply(id, a - STEP, b)) / (2. * STEP);
+            let expected_b = (set.apply(id, a, b + STEP) - set.apply(id, a, b - STEP)) / (2. * STEP);
+            let da = set.apply_dual(id, Dual::variable(a), Dual::constant(b));
+            let db = set.apply_dual(id, Dual::constant(a), Dual::variable(b));
+            assert_eq!(da.value.to_bits(), set.apply(id, a, b).to_bits(), "{}", function.name);
+            for (name, actual, expected) in [("a", da.derivative, expected_a), ("b", db.derivative, expected_b)] {
+                let tolerance = 1e-2 * expected.abs().max(1.);
+                assert!(
+                    (actual - expected).abs() <= tolerance,
+                    "d{}/d{} at ({}, {}): {} instead of {}",
+                    function.name,
+                    name,
+                    a,
+                    b,
+                    actual,
+                    expected
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn pow_partials_stay_finite_at_zero() {
+    let set: FunctionSet = "pow".parse().unwrap();
+    for b in [-1.5, 0., 0.5, 1., 2.] {
+        let da = set.apply_dual(0, Dual::variable(0.), Dual::constant(b));
+        let db = set.apply_dual(0, Dual::constant(0.), Dual::variable(b));
+        assert!(da.derivative.is_finite() && db.derivative.is_finite(), "b = {}", b);
+    }
+}
+
+#[test]
+fn chunked_execution_matches_a_sequential_one() {
+    // parallel chunks, with a last one that is only partly filled
+    let nbr_samples = 3 * ROW_CHUNK_SIZE + 1000;
+    let mut rng = CgpRng::seed_from_u64(0);
+    let a: Vec<f32> = (0..nbr_samples).map(|_| rng.gen_range(-3.0..3.0)).collect();
+    let b: Vec<f32> = (0..nbr_samples).map(|_| rng.gen_range(-3.0..3.0)).collect();
+    let set = FunctionSet { functions: FUNCTIONS.to_vec() };
+    let mut node = Node::new(2, 2, 1, 1, 1, FUNCTIONS.len(), NodeType::ComputationalNode, &mut rng);
+    for (id, function) in FUNCTIONS.iter().enumerate() {
+        node.function_id = id;
+        let mut chunked = vec![0.; nbr_samples];
+        node.execute(&set, &a, &b, &mut chunked);
+        for (i, value) in chunked.iter().enumerate() {
+            assert_eq!(value.to_bits(), set.apply(id, a[i], b[i]).to_bits(), "{} at row {}", function.name, i);
+        }
+    }
+}
+
+#[test]
+fn function_sets_are_selected_by_name() {
+    let default: FunctionSet = "default".parse().unwrap();
+    assert_eq!(default.to_string(), FunctionSet::default().to_string());
+    assert_eq!(default.len(), 14);
+
+    let set: FunctionSet = "sin, pow,add".parse().unwrap();
+    assert_eq!(set.to_string(), "sin,pow,add");
+    assert_eq!((0..set.len()).map(|x| set.arity(x)).collect::<Vec<_>>(), [1, 2, 2]);
+    assert_eq!(set.ids_with_arity(2), [1, 2]);
+    let error = |s: &str| s.parse::<FunctionSet>().err().unwrap();
+    assert!(error("add,foo").contains("unknown function 'foo'"));
+    assert!(error("add,mul,add").contains("listed twice"));
+}
+
+#[test]
+fn nodes_only_use_the_selected_functions() {
+    let params = CgpParameters {
+        nbr_inputs: 2,
+        graph_width: 40,
+        nbr_outputs: 1,
+        function_set: "sin,pow".parse().unwrap(),
+        ..Default::default()
+    };
+    let mut rng = CgpRng::seed_from_u64(2);
+    let mut chromosome = Chromosome::new(params, &mut rng);
+    for _ in 0..50 {
+        chromosome.mutate_prob(0.5, &mut rng);
+    }
+    let computational = &chromosome.nodes_grid[2..42];
+    assert!(computational.iter().all(|x| x.function_id < 2));
+    // printed by name, not by id
+    let printed = chromosome.to_string();
+    assert!(printed.contains("Func: sin") && printed.contains("Func: pow"), "{}", printed);
+    assert!(!printed.contains("Func: 0"));
+}
@@ -0,0 +1,68 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::node::{self, Node};
+use cgp::global_params::CgpParameters;
+use cgp::utils::node_type::NodeType;
+use cgp::utils::random::CgpRng;
+use rand::SeedableRng;
+
+fn valid(position: usize, nbr_inputs: usize, rows: usize, levels_back: usize) -> Vec<usize> {
+    (0..position).filter(|x| node::is_valid_connection(position, *x, nbr_inputs, rows, levels_back)).collect()
+}
+
+#[test]
+fn connections_of_a_hand_laid_out_grid() {
+    // 2 inputs, then columns of 2 rows: nodes 2 3 | 4 5 | 6 7
+    assert_eq!(valid(2, 2, 2, 1), [0, 1]);
+    assert_eq!(valid(3, 2, 2, 1), [0, 1]);
+    // not the own column, and the inputs are out of reach with levels_back 1
+    assert_eq!(valid(5, 2, 2, 1), [2, 3]);
+    assert_eq!(valid(6, 2, 2, 1), [4, 5]);
+    assert_eq!(valid(6, 2, 2, 2), [2, 3, 4, 5]);
+    assert_eq!(valid(7, 2, 2, 3), [0, 1, 2, 3, 4, 5]);
+    // levels_back 0 is no limit, a single row is the old full levels-back
+    assert_eq!(valid(7, 2, 2, 0), [0, 1, 2, 3, 4, 5]);
+    assert_eq!(valid(5, 2, 1, 0), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn connection_ranges_are_the_valid_connections() {
+    let mut rng = CgpRng::seed_from_u64(0);
+    for nbr_inputs in 1..4 {
+        for rows in 1..4 {
+            for levels_back in 0..5 {
+                for position in nbr_inputs..nbr_inputs + 6 * rows {
+                    let node_type = NodeType::ComputationalNode;
+                    let node = Node::new(position, nbr_inputs, 6 * rows, rows, levels_back, 1, node_type, &mut rng);
+                    let range: Vec<usize> = node.connection_range().collect();
+                    let geometry = (nbr_inputs, rows, levels_back, position);
+                    assert_eq!(range, valid(position, nbr_inputs, rows, levels_back), "{:?}", geometry);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn mutation_keeps_the_grid() {
+    let params = CgpParameters {
+        nbr_inputs: 3,
+        graph_width: 12,
+        rows: 3,
+        columns: 4,
+        levels_back: 2,
+        nbr_outputs: 2,
+        ..Default::default()
+    };
+    let mut rng = CgpRng::seed_from_u64(4);
+    let mut chromosome = Chromosome::new(params, &mut rng);
+    for _ in 0..100 {
+        chromosome.mutate_prob(0.3, &mut rng);
+        for position in 3..15 {
+            let node = &chromosome.nodes_grid[position];
+            assert!(node::is_valid_connection(position, node.connection1, 3, 3, 2));
+            assert!(node::is_valid_connection(position, node.connection2, 3, 3, 2));
+        }
+        // output nodes read any input or computational node
+        assert!(chromosome.nodes_grid[15..].iter().all(|x| x.connection1 < 15));
+    }
+}
@@ -0,0 +1,150 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::evaluator::{self, EvaluationBuffer};
+use cgp::global_params::CgpParameters;
+use cgp::utils::node_type::NodeType;
+use cgp::utils::random::CgpRng;
+use cgp::utils::runner::Runner;
+use rand::{Rng, SeedableRng};
+
+const NBR_DATA_INPUTS: usize = 3;
+
+fn params(nbr_constants: usize, linear_scaling: bool) -> CgpParameters {
+    CgpParameters {
+        nbr_inputs: NBR_DATA_INPUTS + nbr_constants,
+        nbr_constants,
+        graph_width: 30,
+        nbr_outputs: 2,
+        linear_scaling,
+        constant_tuning_interval: if nbr_constants > 0 { 3 } else { 0 },
+        ..Default::default()
+    }
+}
+
+fn samples(rng: &mut CgpRng) -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
+    let data: Vec<Vec<f32>> = (0..64)
+        .map(|_| (0..NBR_DATA_INPUTS).map(|_| rng.gen_range(-2.0..2.0)).collect())
+        .collect();
+    let labels = data.iter().map(|x| vec![x[0] * x[1] + x[2], x[0].sin() - 0.5]).collect();
+    (data, labels)
+}
+
+// the genes that decide the outputs: function and the connections it reads of
+// every active node, and the values of the active constants
+fn same_active_genes(child: &Chromosome, parent: &Chromosome) -> bool {
+    if child.active_nodes != parent.active_nodes {
+        return false;
+    }
+    let nbr_data_inputs = child.params.nbr_inputs - child.params.nbr_constants;
+    child.active_nodes.as_ref().unwrap().iter().all(|&id| {
+        let (node, previous) = (&child.nodes_grid[id], &parent.nodes_grid[id]);
+        match node.node_type {
+            NodeType::InputNode if id >= nbr_data_inputs => {
+                child.constants[id - nbr_data_inputs].to_bits() == parent.constants[id - nbr_data_inputs].to_bits()
+            }
+            NodeType::InputNode => true,
+            NodeType::OutputNode => node.connection1 == previous.connection1,
+            NodeType::ComputationalNode => {
+                node.function_id == previous.function_id
+                    && node.connection1 == previous.connection1
+                    && (child.params.function_set.arity(node.function_id) == 1
+                        || node.connection2 == previous.connection2)
+            }
+        }
+    })
+}
+
+fn bits(values: &[f32]) -> Vec<u32> {
+    values.iter().map(|x| x.to_bits()).collect()
+}
+
+#[test]
+fn offspring_evaluation_matches_a_full_evaluation() {
+    for (nbr_constants, linear_scaling) in [(0, false), (0, true), (2, false), (2, true)] {
+        let mut rng = CgpRng::seed_from_u64(nbr_constants as u64 + linear_scaling as u64);
+        let (data, labels) = samples(&mut rng);
+        let inputs = evaluator::to_columns(&data);
+        let mut parent = Chromosome::new(params(nbr_constants, linear_scaling), &mut rng);
+        let mut parent_buffer = EvaluationBuffer::new();
+        let mut parent_fitness = parent.evaluate(&inputs, &labels, &mut parent_buffer);
+        let mut full_buffer = EvaluationBuffer::new();
+        let (mut nbr_reused, mut nbr_evaluated) = (0, 0);
+
+        for _ in 0..3000 {
+            let mut child = parent.clone();
+            match rng.gen_range(0..3) {
+                0 => child.mutate_single(&mut rng),
+                1 => child.mutate_prob(0.05, &mut rng),
+                _ if nbr_constants > 0 => child.mutate_constants(&mut rng),
+                _ => child.mutate_single(&mut rng),
+            }
+            let mut buffer = EvaluationBuffer::new();
+            let incremental = child.evaluate_offspring(&parent, &parent_buffer, &inputs, &labels, &mut buffer);
+            let mut full = child.clone();
+            let full_fitness = full.evaluate(&inputs, &labels, &mut full_buffer);
+
+            assert_eq!(incremental.is_none(), same_active_genes(&child, &parent));
+            // an unchanged offspring is not evaluated at all, its rows are the parent's
+            let rows = if incremental.is_some() { &buffer } else { &parent_buffer };
+            for &id in child.active_nodes.as_ref().unwrap() {
+                assert_eq!(bits(rows.node(id)), bits(full_buffer.node(id)), "node {}", id);
+            }
+            match incremental {
+                Some(fitness) => {
+                    nbr_evaluated += 1;
+                    assert_eq!(fitness.to_bits(), full_fitness.to_bits());
+                    assert_eq!(bits(&child.slopes), bits(&full.slopes));
+                    assert_eq!(bits(&child.intercepts), bits(&full.intercepts));
+                    for (output, expected) in buffer.outputs().iter().zip(full_buffer.outputs()) {
+                        assert_eq!(bits(output), bits(expected));
+                    }
+                }
+                None => {
+                    nbr_reused += 1;
+                    assert_eq!(buffer.nbr_samples(), 0);
+                    assert!(buffer.outputs().is_empty());
+                    assert_eq!(parent_fitness.to_bits(), full_fitness.to_bits());
+                }
+            }
+
+            // (1 + 1) selection, so that the parent and its buffer move on
+            let fitness = incremental.unwrap_or(parent_fitness);
+            if fitness <= parent_fitness {
+                if incremental.is_some() {
+                    std::mem::swap(&mut parent_buffer, &mut buffer);
+                }
+                parent = child;
+                parent_fitness = fitness;
+            }
+        }
+        assert!(nbr_reused > 100 && nbr_evaluated > 100, "{} {}", nbr_reused, nbr_evaluated);
+    }
+}
+
+// the runner keeps the fitnesses from the incremental evaluation and the
+// constant tuning; the parent's has to be the one of a full evaluation. The
+// neutral search takes fitnesses within 1e-4 as equal and prefers offspring,
+// so the parent is not necessarily the exact best
+#[test]
+fn runner_parent_fitness_matches_a_full_evaluation() {
+    for (nbr_constants, linear_scaling) in [(0, false), (2, false), (2, true)] {
+        let mut rng = CgpRng::seed_from_u64(7);
+        let (data, labels) = samples(&mut rng);
+        let inputs = evaluator::to_columns(&data);
+        let params = params(nbr_constants, linear_scaling);
+        let mut runner = Runner::new(params, data, labels.clone(), 0, 0.05, false, 0, rng);
+        let mut buffer = EvaluationBuffer::new();
+        runner.new_parents_by_method(false);
+        for generation in 0..300 {
+            runner.learn_step(false);
+            let fitness = runner.get_parent().evaluate(&inputs, &labels, &mut buffer);
+            let config = (nbr_constants, linear_scaling);
+            assert!(
+                runner.get_fitnesses().iter().any(|x| x.to_bits() == fitness.to_bits()),
+                "{:?} generation {}",
+                config,
+                generation
+            );
+            assert!(fitness - runner.get_best_fitness() <= 1e-4, "{:?} generation {}", config, generation);
+        }
+    }
+}
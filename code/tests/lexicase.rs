@@ -0,0 +1,80 @@
+use cgp::utils::lexicase;
+use cgp::utils::random::CgpRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+// 0 is best on the first two cases, 1 on the last two, 2 is second on all of them
+fn case_errors() -> Vec<Vec<f32>> {
+    vec![vec![0., 0., 3., 3.], vec![2., 2., 0., 0.], vec![1., 1., 1., 1.]]
+}
+
+fn as_slices(errors: &[Vec<f32>]) -> Vec<&[f32]> {
+    errors.iter().map(|x| x.as_slice()).collect()
+}
+
+#[test]
+fn the_first_cases_decide() {
+    let errors = case_errors();
+    let errors = as_slices(&errors);
+    let exact = [0.; 4];
+    for (cases, expected) in [([0, 1, 2, 3], 0), ([1, 3, 0, 2], 0), ([2, 0, 1, 3], 1), ([3, 2, 1, 0], 1)] {
+        let mut candidates = vec![0, 1, 2];
+        lexicase::filter(&errors, &mut candidates, &cases, &exact);
+        assert_eq!(candidates, [expected], "{:?}", cases);
+    }
+}
+
+#[test]
+fn seeded_selection_follows_the_shuffled_cases() {
+    let errors = case_errors();
+    let errors = as_slices(&errors);
+    let mut selected = Vec::new();
+    for seed in 0..20 {
+        let mut rng = CgpRng::seed_from_u64(seed);
+        // the order select will draw
+        let mut order = vec![0, 1, 2, 3];
+        order.shuffle(&mut rng.clone());
+
+        let mut cases = vec![0, 1, 2, 3];
+        let parents = lexicase::select(&errors, vec![0, 1, 2], 1, &mut cases, &[0.; 4], &mut rng);
+        assert_eq!(cases, order);
+        let expected = if order[0] < 2 { 0 } else { 1 };
+        assert_eq!(parents, [expected], "seed {}", seed);
+        selected.push(parents[0]);
+    }
+    assert!(selected.contains(&0) && selected.contains(&1));
+
+    // a selected parent leaves the pool
+    let mut cases = vec![0, 1, 2, 3];
+    let mut parents = lexicase::select(&errors, vec![0, 1, 2], 3, &mut cases, &[0.; 4], &mut CgpRng::seed_from_u64(1));
+    parents.sort();
+    assert_eq!(parents, [0, 1, 2]);
+}
+
+#[test]
+fn median_absolute_deviation_of_the_finite_values() {
+    // median 3, deviations 2 1 1 4
+    assert_eq!(lexicase::median_absolute_deviation(&[7., 1., f32::INFINITY, 4., f32::NAN, 2.]), 1.5);
+    assert_eq!(lexicase::median_absolute_deviation(&[5., 1., 2.]), 1.);
+    assert_eq!(lexicase::median_absolute_deviation(&[f32::INFINITY, f32::NAN]), 0.);
+    assert_eq!(lexicase::median_absolute_deviation(&[]), 0.);
+}
+
+#[test]
+fn epsilon_keeps_the_near_best() {
+    let errors = case_errors();
+    let errors = as_slices(&errors);
+    // every case has the errors 0 1 2 or 3 0 1 over the pool: a deviation of 1
+    let epsilons = lexicase::epsilons(&errors, &[0, 1, 2], &[0, 1, 2, 3]);
+    assert_eq!(epsilons, [1.; 4]);
+    // only the sampled cases and the pool count
+    assert_eq!(lexicase::epsilons(&errors, &[0, 1], &[2]), [0., 0., 1.5, 0.]);
+
+    // 2 is within 1 of 0 on the first cases and then the best left on the third
+    let mut candidates = vec![0, 1, 2];
+    lexicase::filter(&errors, &mut candidates, &[0, 1, 2, 3], &epsilons);
+    assert_eq!(candidates, [2]);
+    let mut candidates = vec![0, 1, 2];
+    lexicase::filter(&errors, &mut candidates, &[0, 1, 2, 3], &[0.; 4]);
+    assert_eq!(candidates, [0]);
+}
@@ -0,0 +1,66 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::evaluator::{self, EvaluationBuffer};
+use cgp::global_params::CgpParameters;
+use cgp::utils::random::CgpRng;
+use rand::SeedableRng;
+
+// the output node 2 reads x0, or node 1 computing x0 - x0 when constant is set
+fn chromosome(linear_scaling: bool, constant: bool) -> Chromosome {
+    let params = CgpParameters {
+        nbr_inputs: 1,
+        graph_width: 1,
+        nbr_outputs: 1,
+        linear_scaling,
+        function_set: "sub".parse().unwrap(),
+        ..Default::default()
+    };
+    let mut chromosome = Chromosome::new(params, &mut CgpRng::seed_from_u64(0));
+    let node = &mut chromosome.nodes_grid[1];
+    (node.function_id, node.connection1, node.connection2) = (0, 0, 0);
+    chromosome.nodes_grid[2].connection1 = if constant { 1 } else { 0 };
+    chromosome
+}
+
+fn line(xs: &[f32], slope: f32, intercept: f32) -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
+    let data: Vec<Vec<f32>> = xs.iter().map(|x| vec![*x]).collect();
+    let labels: Vec<Vec<f32>> = xs.iter().map(|x| vec![slope * x + intercept]).collect();
+    (data, labels)
+}
+
+#[test]
+fn slope_and_intercept_are_fitted_and_kept() {
+    let (data, labels) = line(&[-2., -1., 0., 1., 2., 3.], 3., 2.);
+    let mut buffer = EvaluationBuffer::new();
+    let mut chromosome = chromosome(true, false);
+    let fitness = chromosome.evaluate(&evaluator::to_columns(&data), &labels, &mut buffer);
+    assert!(fitness < 1e-8, "{}", fitness);
+    assert!((chromosome.slopes[0] - 3.).abs() < 1e-5 && (chromosome.intercepts[0] - 2.).abs() < 1e-5);
+
+    // predictions and held-out scores use the stored transform, they do not refit
+    let (test_data, _) = line(&[10., -7.], 3., 2.);
+    let predictions = chromosome.predict(&evaluator::to_columns(&test_data), &mut buffer);
+    assert!((predictions[0][0] - 32.).abs() < 1e-4 && (predictions[1][0] + 19.).abs() < 1e-4, "{:?}", predictions);
+    let (test_data, shifted) = line(&[10., -7.], 3., 5.);
+    let score = chromosome.score(&evaluator::to_columns(&test_data), &shifted, &mut buffer);
+    assert!((score - 9.).abs() < 1e-3, "{}", score);
+}
+
+#[test]
+fn without_linear_scaling_the_raw_output_is_scored() {
+    let (data, labels) = line(&[0., 1., 2.], 3., 2.);
+    let mut chromosome = chromosome(false, false);
+    let fitness = chromosome.evaluate(&evaluator::to_columns(&data), &labels, &mut EvaluationBuffer::new());
+    // errors 2 4 6
+    assert!((fitness - 56. / 3.).abs() < 1e-4, "{}", fitness);
+    assert_eq!((chromosome.slopes[0], chromosome.intercepts[0]), (1., 0.));
+}
+
+#[test]
+fn a_constant_output_gets_the_label_mean() {
+    let (data, labels) = line(&[0., 1., 2., 5.], 2., 1.);
+    let mut chromosome = chromosome(true, true);
+    let fitness = chromosome.evaluate(&evaluator::to_columns(&data), &labels, &mut EvaluationBuffer::new());
+    // labels 1 3 5 11 with mean 5, the fitness is their variance
+    assert_eq!((chromosome.slopes[0], chromosome.intercepts[0]), (0., 5.));
+    assert!((fitness - 14.).abs() < 1e-5, "{}", fitness);
+}
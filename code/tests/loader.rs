@@ -0,0 +1,95 @@
+use cgp::datasets::loader::{self, DatasetError, DatasetSchema, Encoding, Target};
+use cgp::datasets::{abalone, car};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// a data root holding one file at the schema's relative path
+fn data_root(name: &str, file_name: &str, contents: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("cgp_loader_{}_{}", name, std::process::id()));
+    let path = root.join(file_name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+    root
+}
+
+#[test]
+fn abalone_is_read_below_the_data_root() {
+    let rows = "M,0.455,0.365,0.095,0.514,0.2245,0.101,0.15,15\n\nI,0.33,0.255,0.08,0.205,0.0895,0.0395,0.055,7\n";
+    let root = data_root("abalone", abalone::schema().file_name, rows);
+    let (data, labels) = abalone::get_dataset(&root).unwrap();
+    assert_eq!(data.len(), 2);
+    assert_eq!((data[0][0], data[1][0]), (0., 1.));
+    assert_eq!(data[1][1..], [0.33, 0.255, 0.08, 0.205, 0.0895, 0.0395, 0.055]);
+    assert_eq!(labels, [[15.], [7.]]);
+    let names = loader::feature_names(&abalone::schema(), &root).unwrap();
+    assert_eq!(names[0], "Sex");
+    assert_eq!(names.len(), 8);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn a_missing_file_is_an_error_naming_its_path() {
+    let root = Path::new("/nonexistent/cgp_data");
+    match car::get_dataset(root) {
+        Err(DatasetError::Io { path, .. }) => assert_eq!(path, root.join(car::schema().file_name)),
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    assert_eq!(loader::resolve_data_root(Some(root.to_path_buf())), root);
+}
+
+#[test]
+fn bad_records_are_reported_with_their_line() {
+    // an unknown ordinal value is missing, an unknown class is an error
+    let rows = "vhigh,low,2,4,big,high,acc\nfoo,med,3,2,small,low,unacc\n";
+    let root = data_root("car", car::schema().file_name, &format!("{}low,low,2,2,med,med,great\n", rows));
+    match car::get_dataset(&root) {
+        Err(DatasetError::UnknownClass { line, value, .. }) => assert_eq!((line, value.as_str()), (3, "great")),
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    fs::remove_dir_all(&root).unwrap();
+
+    let root = data_root("car_ok", car::schema().file_name, rows);
+    let (data, labels) = car::get_dataset(&root).unwrap();
+    assert_eq!(data[0], [0., 3., 2., 4., 2., 2.]);
+    assert!(data[1][0].is_nan());
+    assert_eq!(labels, [[0., 1., 0., 0.], [1., 0., 0., 0.]]);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+fn quoted_schema() -> DatasetSchema {
+    DatasetSchema {
+        file_name: "quoted.csv",
+        delimiter: ';',
+        header_rows: 1,
+        column_names: &[],
+        missing_token: Some("NA"),
+        strip_quotes: true,
+        features: vec![loader::column(0, Encoding::Numeric), loader::column(2, Encoding::Numeric)],
+        target: Target::Regression(1),
+    }
+}
+
+#[test]
+fn header_quotes_and_missing_tokens() {
+    let root = data_root("quoted", "quoted.csv", "\"a\";\"y\";\"b\"\n\"1.5\";2;NA\n3;4;\" 5 \"\n");
+    let (data, labels) = loader::load(&quoted_schema(), &root).unwrap();
+    assert_eq!(data[0][0], 1.5);
+    assert!(data[0][1].is_nan());
+    assert_eq!(data[1], [3., 5.]);
+    assert_eq!(labels, [[2.], [4.]]);
+    assert_eq!(loader::feature_names(&quoted_schema(), &root).unwrap(), ["a", "b"]);
+    fs::remove_dir_all(&root).unwrap();
+
+    let root = data_root("invalid", "quoted.csv", "a;y;b\n1;2;3\n1;x;3\n");
+    match loader::load(&quoted_schema(), &root) {
+        Err(DatasetError::InvalidValue { line, column, value, .. }) => {
+            assert_eq!((line, column, value.as_str()), (3, 1, "x"))
+        }
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    fs::remove_dir_all(&root).unwrap();
+
+    let root = data_root("empty", "quoted.csv", "a;y;b\n\n");
+    assert!(matches!(loader::load(&quoted_schema(), &root), Err(DatasetError::Empty { .. })));
+    fs::remove_dir_all(&root).unwrap();
+}
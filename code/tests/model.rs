@@ -0,0 +1,79 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::evaluator::{self, EvaluationBuffer};
+use cgp::cgp_es::model::{Model, ModelError, FORMAT_VERSION};
+use cgp::datasets::imputation::ImputationStrategy;
+use cgp::datasets::preprocessing::{Preprocessing, Preprocessor};
+use cgp::datasets::scaling::ScalingMethod;
+use cgp::global_params::CgpParameters;
+use cgp::utils::fitness_metrics::RegressionMetric;
+use cgp::utils::random::CgpRng;
+use rand::{Rng, SeedableRng};
+use std::fs;
+use std::path::PathBuf;
+
+fn bits(x: &[Vec<f32>]) -> Vec<u32> {
+    x.iter().flatten().map(|x| x.to_bits()).collect()
+}
+
+fn model_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cgp_{}_{}.json", name, std::process::id()))
+}
+
+// an evaluated chromosome with constants and linear scaling, on raw data with
+// a missing value, and that data preprocessed
+fn fitted_model() -> (Model, Vec<Vec<f32>>) {
+    let mut rng = CgpRng::seed_from_u64(7);
+    let mut raw: Vec<Vec<f32>> = (0..50).map(|_| vec![rng.gen_range(-2.0..2.0), rng.gen_range(0.0..10.0)]).collect();
+    raw[3][1] = f32::NAN;
+    let raw_labels: Vec<Vec<f32>> = raw.iter().map(|x| vec![x[0] * x[0] + 3.]).collect();
+    let config = Preprocessing {
+        imputation: ImputationStrategy::Median,
+        scaling: ScalingMethod::ZScore,
+        target_scaling: ScalingMethod::MinMax,
+    };
+    let preprocessor = Preprocessor::fit(&config, &raw, &raw_labels);
+    let (mut data, mut labels) = (raw.clone(), raw_labels);
+    preprocessor.transform(&mut data, &mut labels);
+
+    let params = CgpParameters {
+        nbr_inputs: 4,
+        nbr_constants: 2,
+        graph_width: 30,
+        nbr_outputs: 1,
+        metric: RegressionMetric::Huber(0.5),
+        linear_scaling: true,
+        function_set: "add,mul,sin,pow".parse().unwrap(),
+        ..Default::default()
+    };
+    let mut chromosome = Chromosome::new(params, &mut rng);
+    chromosome.evaluate(&evaluator::to_columns(&data), &labels, &mut EvaluationBuffer::new());
+    (Model::new(chromosome, preprocessor, "test", 7, 0), raw)
+}
+
+#[test]
+fn loaded_model_predicts_identically() {
+    let (mut model, raw) = fitted_model();
+    let path = model_path("roundtrip");
+    model.save(&path).unwrap();
+    let mut loaded = Model::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.chromosome.params.metric, RegressionMetric::Huber(0.5));
+    assert_eq!(loaded.chromosome.params.function_set.to_string(), "add,mul,sin,pow");
+    assert_eq!(format!("{}", loaded.chromosome), format!("{}", model.chromosome));
+    assert_eq!(bits(&loaded.predict(&raw)), bits(&model.predict(&raw)));
+}
+
+#[test]
+fn other_format_versions_are_rejected() {
+    let (model, _) = fitted_model();
+    let path = model_path("version");
+    model.save(&path).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    let old = format!("\"version\": {}", FORMAT_VERSION);
+    fs::write(&path, text.replacen(&old, "\"version\": 0", 1)).unwrap();
+    let result = Model::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert!(matches!(result, Err(ModelError::Version { found: 0, .. })));
+}
@@ -0,0 +1,89 @@
+use cgp::cgp_es::chromosome::Chromosome;
+use cgp::cgp_es::evaluator::{self, EvaluationBuffer};
+use cgp::datasets::dataset::TaskKind;
+use cgp::datasets::loader::{self, DatasetError, DatasetSchema, Samples, Target};
+use cgp::global_params::CgpParameters;
+use cgp::utils::random::CgpRng;
+use rand::SeedableRng;
+use std::fs;
+
+// x, y0 = x, y1 = x + 1
+fn load_two_targets() -> Samples {
+    load("two_targets", "x,y0,y1\n0,0,1\n1,1,2\n2,2,3\n3,3,4\n").unwrap()
+}
+
+fn load(name: &str, contents: &str) -> Result<Samples, DatasetError> {
+    let data_root = std::env::temp_dir().join(format!("cgp_multi_output_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&data_root).unwrap();
+    fs::write(data_root.join("two_targets.csv"), contents).unwrap();
+    let schema = DatasetSchema {
+        file_name: "two_targets.csv",
+        delimiter: ',',
+        header_rows: 1,
+        column_names: &[],
+        missing_token: None,
+        strip_quotes: false,
+        features: loader::numeric(0..1),
+        target: Target::MultiRegression(&[1, 2]),
+    };
+    let samples = loader::load(&schema, &data_root);
+    fs::remove_dir_all(&data_root).unwrap();
+    samples
+}
+
+// both outputs read the input directly, so y0 is exact and y1 is off by one
+fn identity_chromosome(linear_scaling: bool) -> Chromosome {
+    let params = CgpParameters {
+        nbr_inputs: 1,
+        graph_width: 5,
+        nbr_outputs: 2,
+        task: TaskKind::Regression,
+        linear_scaling,
+        ..Default::default()
+    };
+    let mut chromosome = Chromosome::new(params, &mut CgpRng::seed_from_u64(0));
+    for &id in &chromosome.output_node_ids.clone() {
+        chromosome.nodes_grid[id].connection1 = 0;
+    }
+    chromosome
+}
+
+#[test]
+fn multi_regression_schema_gives_one_label_column_per_target() {
+    let (data, labels) = load_two_targets();
+    assert_eq!(data, [[0.], [1.], [2.], [3.]]);
+    assert_eq!(labels, [[0., 1.], [1., 2.], [2., 3.], [3., 4.]]);
+}
+
+#[test]
+fn every_target_column_has_to_be_present() {
+    // the third line lacks y1
+    match load("missing", "x,y0,y1\n0,0,1\n1,1\n") {
+        Err(DatasetError::MissingColumn { line, column, .. }) => assert_eq!((line, column), (3, 2)),
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    match load("invalid", "x,y0,y1\n0,0,1\n1,one,2\n") {
+        Err(DatasetError::InvalidValue { line, column, .. }) => assert_eq!((line, column), (3, 1)),
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fitness_is_the_mean_of_the_per_output_errors() {
+    let (data, labels) = load_two_targets();
+    let inputs = evaluator::to_columns(&data);
+    let mut buffer = EvaluationBuffer::new();
+
+    let mut chromosome = identity_chromosome(false);
+    let fitness = chromosome.evaluate(&inputs, &labels, &mut buffer);
+    assert_eq!(chromosome.get_output_errors(), [0., 1.]);
+    assert_eq!(fitness, 0.5);
+    assert_eq!(chromosome.get_mae(), 0.5);
+
+    // the intercept of the second output absorbs the offset
+    let mut chromosome = identity_chromosome(true);
+    let fitness = chromosome.evaluate(&inputs, &labels, &mut buffer);
+    assert_eq!(chromosome.get_output_errors(), [0., 0.]);
+    assert_eq!(fitness, 0.);
+    assert_eq!(chromosome.intercepts, [0., 1.]);
+}
@@ -0,0 +1,71 @@
+use cgp::global_params::CgpParameters;
+use cgp::utils::pareto;
+use cgp::utils::random::CgpRng;
+use cgp::utils::runner::Runner;
+use rand::{Rng, SeedableRng};
+
+#[test]
+fn fronts_of_a_hand_sorted_population() {
+    let objectives = vec![
+        vec![1., 5.],
+        vec![2., 3.],
+        vec![4., 1.],
+        // dominated by 1 and 5 only
+        vec![3., 4.],
+        // dominated by 0 as well, so it comes after 3
+        vec![5., 5.],
+        // equal to 1, neither dominates the other
+        vec![2., 3.],
+    ];
+    assert_eq!(pareto::non_dominated_sort(&objectives), [vec![0, 1, 2, 5], vec![3], vec![4]]);
+    assert!(pareto::non_dominated_sort(&[]).is_empty());
+}
+
+#[test]
+fn crowding_distance_of_a_hand_computed_front() {
+    let objectives = vec![vec![1., 6.], vec![2., 4.], vec![3., 3.], vec![5., 1.], vec![9., 9.]];
+    // ranges 4 and 5: (3 - 1) / 4 + (6 - 3) / 5 and (5 - 2) / 4 + (4 - 1) / 5
+    let distances = pareto::crowding_distance(&objectives, &[0, 1, 2, 3]);
+    assert_eq!(distances[0], f32::INFINITY);
+    assert!((distances[1] - 1.1).abs() < 1e-6, "{}", distances[1]);
+    assert!((distances[2] - 1.35).abs() < 1e-6, "{}", distances[2]);
+    assert_eq!(distances[3], f32::INFINITY);
+    // in the order of the front, not of the population
+    let distances = pareto::crowding_distance(&objectives, &[3, 1, 0, 2]);
+    assert_eq!(distances[0], f32::INFINITY);
+    assert!((distances[1] - 1.1).abs() < 1e-6);
+    assert!((distances[3] - 1.35).abs() < 1e-6);
+    // up to two members are all boundaries
+    assert_eq!(pareto::crowding_distance(&objectives, &[1, 2]), [f32::INFINITY; 2]);
+}
+
+#[test]
+fn crowding_distance_skips_objectives_without_range() {
+    let objectives = vec![vec![1., 2.], vec![2., 2.], vec![4., 2.]];
+    assert_eq!(pareto::crowding_distance(&objectives, &[0, 1, 2]), [f32::INFINITY, 1., f32::INFINITY]);
+}
+
+#[test]
+fn pareto_front_keeps_the_best_of_the_whole_run() {
+    let mut rng = CgpRng::seed_from_u64(5);
+    let data: Vec<Vec<f32>> = (0..40).map(|_| vec![rng.gen_range(-1.0..1.0)]).collect();
+    let labels: Vec<Vec<f32>> = data.iter().map(|x| vec![x[0] * x[0] + x[0]]).collect();
+    let params = CgpParameters { nbr_inputs: 1, graph_width: 20, nbr_outputs: 1, mu: 2, lambda: 4, ..Default::default() };
+    let mut runner = Runner::new(params, data.clone(), labels.clone(), 0, 0.05, false, 6, rng);
+    runner.new_parents_by_method(false);
+    let mut best = runner.get_best_fitness();
+    for _ in 0..200 {
+        best = best.min(runner.learn_step(false));
+    }
+
+    let front = runner.pareto_front(data, labels);
+    assert_eq!(front.iter().map(|x| x.train_fitness).fold(f32::INFINITY, f32::min), best);
+    for (a, b) in front.iter().zip(&front[1..]) {
+        // sorted by complexity, so each step has to buy a better fitness
+        assert!(a.complexity < b.complexity && a.train_fitness > b.train_fitness);
+    }
+    // the test data is the training data here
+    for member in &front {
+        assert_eq!(member.test_fitness, member.train_fitness);
+    }
+}
@@ -0,0 +1,122 @@
+use cgp::datasets::loader::DatasetError;
+use cgp::datasets::pla::{self, MaskedBooleanSamples};
+use cgp::global_params::CgpParameters;
+use cgp::utils::fitness_metrics;
+use ndarray::{array, Array2};
+use std::fs;
+use std::path::Path;
+
+// rows 00 01 10 11; the first cube sets row 00 and 01, the second row 11
+fn load(pla_type: &str) -> MaskedBooleanSamples {
+    let contents = format!(
+        "# two outputs over two inputs\n.i 2\n.o 2\n.ilb a b\n.ob y z\n.type {}\n.p 2\n0- |10\n11 0-\n.e\n",
+        pla_type
+    );
+    let path = std::env::temp_dir().join(format!("cgp_{}_{}.pla", pla_type, std::process::id()));
+    fs::write(&path, contents).unwrap();
+    let samples = pla::load_pla(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    samples
+}
+
+type Loader = fn(&Path) -> Result<MaskedBooleanSamples, DatasetError>;
+
+fn load_error(name: &str, contents: &str, load: Loader) -> DatasetError {
+    let path = std::env::temp_dir().join(format!("cgp_error_{}_{}.pla", name, std::process::id()));
+    fs::write(&path, contents).unwrap();
+    let result = load(&path);
+    fs::remove_file(&path).unwrap();
+    result.err().unwrap()
+}
+
+#[test]
+fn pla_header_and_rows() {
+    let samples = load("fd");
+    assert_eq!(samples.input_names, ["a", "b"]);
+    assert_eq!(samples.output_names, ["y", "z"]);
+    assert_eq!(samples.inputs, array![[false, false], [false, true], [true, false], [true, true]]);
+}
+
+#[test]
+fn pla_types_decide_off_set_and_dont_cares() {
+    // f: only the 1s count, everything else is off
+    let f = load("f");
+    assert_eq!(f.labels, array![[true, false], [true, false], [false, false], [false, false]]);
+    assert_eq!(f.mask, Array2::from_elem((4, 2), true));
+
+    // fd: '-' outputs are don't cares, unlisted rows are off
+    let fd = load("fd");
+    assert_eq!(fd.labels, f.labels);
+    assert_eq!(fd.mask, array![[true, true], [true, true], [true, true], [true, false]]);
+
+    // fr: '0' outputs are off, unlisted rows and '-' are don't cares
+    let fr = load("fr");
+    assert_eq!(fr.labels, f.labels);
+    assert_eq!(fr.mask, array![[true, true], [true, true], [false, false], [true, false]]);
+
+    // fdr: like fr, here '-' is an explicit don't care
+    let fdr = load("fdr");
+    assert_eq!(fdr.labels, f.labels);
+    assert_eq!(fdr.mask, fr.mask);
+}
+
+#[test]
+fn masked_fitness_ignores_dont_cares() {
+    let labels = array![[true, false], [false, true]];
+    let mask = array![[true, false], [true, true]];
+    // wrong only where the mask is false
+    let outputs = array![[true, true], [false, true]];
+    assert_eq!(fitness_metrics::fitness_boolean_masked(&outputs.view(), &labels, &mask), 0.);
+    assert_eq!(fitness_metrics::fitness_boolean(&outputs.view(), &labels), 0.25);
+    // one of the three cared outputs wrong
+    let outputs = array![[true, true], [true, true]];
+    assert_eq!(fitness_metrics::fitness_boolean_masked(&outputs.view(), &labels, &mask), 1. / 3.);
+    // nothing cared about is a perfect fit
+    let nothing = Array2::from_elem((2, 2), false);
+    assert_eq!(fitness_metrics::fitness_boolean_masked(&outputs.view(), &labels, &nothing), 0.);
+}
+
+// every bool-valued chromosome type has its own copy of evaluate_masked
+macro_rules! check_evaluate_masked {
+    ($chromosome:ty) => {{
+        let samples = load("fr");
+        let flipped =
+            Array2::from_shape_fn(samples.labels.dim(), |(i, j)| samples.labels[[i, j]] ^ !samples.mask[[i, j]]);
+        let full_mask = Array2::from_elem(samples.labels.dim(), true);
+        let params = CgpParameters { nbr_inputs: 2, graph_width: 10, nbr_outputs: 2, ..Default::default() };
+        for _ in 0..20 {
+            let mut chromosome = <$chromosome>::new(params.clone());
+            let fitness = chromosome.evaluate_masked(&samples.inputs, &samples.labels, &samples.mask);
+            assert_eq!(fitness, chromosome.evaluate_masked(&samples.inputs, &flipped, &samples.mask));
+            assert_eq!(
+                chromosome.evaluate_masked(&samples.inputs, &samples.labels, &full_mask),
+                chromosome.evaluate(&samples.inputs, &samples.labels)
+            );
+        }
+    }};
+}
+
+#[test]
+fn evaluate_masked_does_not_depend_on_dont_care_labels() {
+    check_evaluate_masked!(cgp::vanilla_cgp::chromosome::Chromosome);
+    check_evaluate_masked!(cgp::dag::chromosome_dag::Chromosome);
+    check_evaluate_masked!(cgp::reorder::chromosome_reorder::Chromosome);
+    check_evaluate_masked!(cgp::reorder::chromosome_reorder_equidistant::Chromosome);
+}
+
+#[test]
+fn cubes_of_the_wrong_arity_are_rejected() {
+    // a cube with three input values under .i 2, and one with a missing output
+    for (name, cube) in [("long", "011 1"), ("short", "01")] {
+        let error = load_error(name, &format!(".i 2\n.o 1\n00 1\n{}\n.e\n", cube), pla::load_pla);
+        assert!(matches!(error, DatasetError::Malformed { line: 4, .. }), "{}", error);
+        assert!(error.to_string().contains("expected 2 input and 1 output values"), "{}", error);
+    }
+    let error = load_error("value", ".i 2\n.o 1\n0a 1\n", pla::load_pla);
+    assert!(matches!(error, DatasetError::InvalidValue { line: 3, .. }), "{}", error);
+    assert!(load_error("header", ".o 1\n00 1\n", pla::load_pla).to_string().contains("missing .i"));
+
+    // a truth table row that does not match the header
+    let error = load_error("table", "a,b,y\n0,0,1\n0,1\n", |path| pla::load_truth_table(path, 1));
+    assert!(matches!(error, DatasetError::Malformed { line: 3, .. }), "{}", error);
+}
@@ -0,0 +1,63 @@
+use cgp::datasets::imputation::{ImputationStrategy, Imputer};
+use cgp::datasets::preprocessing::{Preprocessing, Preprocessor};
+use cgp::datasets::scaling::ScalingMethod;
+
+const NAN: f32 = f32::NAN;
+
+// observed values: 1 3 8, 2 4 6, and a complete constant column
+fn train() -> Vec<Vec<f32>> {
+    vec![vec![1., NAN, 5.], vec![NAN, 2., 5.], vec![3., 4., 5.], vec![8., 6., 5.]]
+}
+
+#[test]
+fn mean_and_median_use_the_observed_values() {
+    let mean = Imputer::fit(ImputationStrategy::Mean, &train());
+    assert_eq!(mean.fill_values, [4., 4., 5.]);
+    assert!(mean.indicator_columns.is_empty());
+    let median = Imputer::fit(ImputationStrategy::Median, &train());
+    assert_eq!(median.fill_values, [3., 4., 5.]);
+    // even count: the mean of the two middle values; nothing observed: 0
+    let even = Imputer::fit(ImputationStrategy::Median, &[vec![10., NAN], vec![1., NAN], vec![3., NAN], vec![2., NAN]]);
+    assert_eq!(even.fill_values, [2.5, 0.]);
+
+    let mut data = train();
+    assert_eq!(median.transform(&mut data), [1, 1, 0]);
+    assert_eq!(data, [[1., 4., 5.], [3., 2., 5.], [3., 4., 5.], [8., 6., 5.]]);
+}
+
+#[test]
+fn indicator_columns_come_from_the_fitted_rows() {
+    let imputer = Imputer::fit(ImputationStrategy::MissingIndicator, &train());
+    assert_eq!(imputer.fill_values, [4., 4., 5.]);
+    assert_eq!(imputer.indicator_columns, [0, 1]);
+    // the last column was complete when fitting, so it gets no indicator
+    let mut data = vec![vec![NAN, 1., NAN], vec![2., NAN, 7.]];
+    assert_eq!(imputer.transform(&mut data), [1, 1, 1]);
+    assert_eq!(data, [[4., 1., 5., 1., 0.], [2., 4., 7., 0., 1.]]);
+}
+
+#[test]
+fn preprocessor_is_fitted_on_train_and_applied_to_test() {
+    let config = Preprocessing {
+        imputation: ImputationStrategy::MissingIndicator,
+        scaling: ScalingMethod::MinMax,
+        target_scaling: ScalingMethod::ZScore,
+    };
+    let mut data = train();
+    let mut labels = vec![vec![0.], vec![2.], vec![4.], vec![6.]];
+    let preprocessor = Preprocessor::fit(&config, &data, &labels);
+    // after imputation: 1 4 3 8 and 4 2 4 6; the indicators are not scaled
+    assert_eq!(preprocessor.scaler.offsets, [1., 2., 5.]);
+    assert_eq!(preprocessor.scaler.scales, [7., 4., 1.]);
+    assert_eq!(preprocessor.target_scaler.offsets, [3.]);
+    preprocessor.transform(&mut data, &mut labels);
+    assert_eq!(data[1], [3. / 7., 0., 0., 1., 0.]);
+
+    // test values outside the training range and a missing value in a column
+    // that was complete in training: training statistics, no new indicator
+    let mut test_data = vec![vec![15., 10., NAN], vec![NAN, NAN, 6.]];
+    let mut test_labels = vec![vec![3.], vec![9.]];
+    assert_eq!(preprocessor.transform(&mut test_data, &mut test_labels), [1, 1, 1]);
+    assert_eq!(test_data, [[2., 2., 0., 0., 0.], [3. / 7., 0.5, 1., 1., 1.]]);
+    assert_eq!(test_labels[0], [0.]);
+}
@@ -0,0 +1,67 @@
+use cgp::datasets::dataset::TaskKind;
+use cgp::datasets::loader::DatasetError;
+use cgp::datasets::sr_benchmarks::BenchmarkSettings;
+use cgp::datasets::{self, iris};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+fn settings() -> BenchmarkSettings {
+    BenchmarkSettings { sample_count: Some(10), seed: 1 }
+}
+
+#[test]
+fn names_are_unique_and_ids_keep_the_old_numbering() {
+    let registry = datasets::registry(&settings());
+    let names: HashSet<&str> = registry.iter().map(|x| x.name()).collect();
+    assert_eq!(names.len(), registry.len());
+    assert_eq!(registry[0].name(), "koza");
+    assert_eq!(registry[14].name(), "adult");
+    assert_eq!(registry[22].name(), "wine_quality");
+    // the old `dataset < 14` rule, now metadata of every dataset
+    for (id, dataset) in registry.iter().enumerate().take(23) {
+        let expected = if id < 14 { TaskKind::Regression } else { TaskKind::Classification };
+        assert_eq!(dataset.task(), expected, "{}", dataset.name());
+    }
+    // the benchmark suite follows, all regression
+    assert!(registry[23..].iter().all(|x| x.task() == TaskKind::Regression));
+}
+
+#[test]
+fn datasets_are_found_by_name_or_id() {
+    let (id, dataset) = datasets::find("car", &settings()).unwrap();
+    assert_eq!((id, dataset.name()), (16, "car"));
+    assert_eq!(datasets::find("16", &settings()).unwrap().1.name(), "car");
+    assert_eq!(datasets::find("nguyen-1", &settings()).unwrap().1.task(), TaskKind::Regression);
+    assert!(datasets::find("no_such_dataset", &settings()).is_none());
+    assert!(datasets::find("100000", &settings()).is_none());
+}
+
+#[test]
+fn metadata_comes_from_the_dataset() {
+    let root = Path::new("/nonexistent/cgp_data");
+    let (_, car) = datasets::find("car", &settings()).unwrap();
+    assert_eq!(car.class_names(root).unwrap(), ["unacc", "acc", "good", "vgood"]);
+    assert_eq!(car.feature_names(root).unwrap()[..2], ["buying", "maint"]);
+    assert!(matches!(car.load(root), Err(DatasetError::Io { .. })));
+
+    // benchmarks need no files and bring their own test set
+    let (_, koza) = datasets::find("koza", &settings()).unwrap();
+    assert!(koza.class_names(root).unwrap().is_empty());
+    assert_eq!(koza.feature_names(root).unwrap(), ["x0"]);
+    let ((train, _), (test, _)) = koza.load_partitioned(root).unwrap().unwrap();
+    assert_eq!(train.len(), 10);
+    assert!(!test.is_empty());
+
+    // arff datasets read their names from the header
+    let root = std::env::temp_dir().join(format!("cgp_registry_{}", std::process::id()));
+    let path = root.join(iris::FILE_NAME);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let header = "@relation iris\n@attribute sepallength real\n@attribute petalwidth real\n";
+    fs::write(&path, format!("{}@attribute class {{setosa,virginica}}\n@data\n5.1,0.2,setosa\n", header)).unwrap();
+    let (_, iris) = datasets::find("iris", &settings()).unwrap();
+    assert_eq!(iris.feature_names(&root).unwrap(), ["sepallength", "petalwidth"]);
+    assert_eq!(iris.class_names(&root).unwrap(), ["setosa", "virginica"]);
+    assert_eq!(iris.load(&root).unwrap(), (vec![vec![5.1, 0.2]], vec![vec![1., 0.]]));
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,66 @@
+use cgp::datasets::scaling::{Scaler, ScalingMethod};
+
+const METHODS: [ScalingMethod; 4] = [ScalingMethod::MinMax, ScalingMethod::ZScore, ScalingMethod::Robust, ScalingMethod::None];
+
+// the second column is constant
+fn train() -> Vec<Vec<f32>> {
+    vec![vec![2., 7.], vec![4., 7.], vec![10., 7.], vec![-6., 7.], vec![0., 7.]]
+}
+
+fn assert_close(values: &[Vec<f32>], expected: &[Vec<f32>]) {
+    for (row, expected_row) in values.iter().zip(expected) {
+        for (value, expected) in row.iter().zip(expected_row) {
+            assert!((value - expected).abs() < 1e-5, "{:?} != {:?}", values, expected);
+        }
+    }
+}
+
+#[test]
+fn fitted_statistics() {
+    let minmax = Scaler::fit(ScalingMethod::MinMax, &train());
+    assert_eq!((minmax.offsets[0], minmax.scales[0]), (-6., 16.));
+    // mean 2, population standard deviation sqrt(136 / 5)
+    let zscore = Scaler::fit(ScalingMethod::ZScore, &train());
+    assert_eq!(zscore.offsets[0], 2.);
+    assert!((zscore.scales[0] - 27.2f32.sqrt()).abs() < 1e-5);
+    // sorted -6 0 2 4 10: median 2, quartiles 0 and 4
+    let robust = Scaler::fit(ScalingMethod::Robust, &train());
+    assert_eq!((robust.offsets[0], robust.scales[0]), (2., 4.));
+}
+
+#[test]
+fn constant_columns_are_only_shifted() {
+    for method in METHODS {
+        let scaler = Scaler::fit(method, &train());
+        assert_eq!(scaler.scales[1], 1., "{}", method);
+        let mut data = train();
+        scaler.transform(&mut data);
+        assert!(data.iter().all(|x| x.iter().all(|x| x.is_finite())), "{}", method);
+        if method != ScalingMethod::None {
+            assert!(data.iter().all(|x| x[1] == 0.), "{}", method);
+        }
+    }
+}
+
+#[test]
+fn outputs_are_mapped_back_to_the_original_units() {
+    let test = vec![vec![12., 7.], vec![-1., 3.]];
+    for method in METHODS {
+        // fitted on train only, also applied to test
+        let scaler = Scaler::fit(method, &train());
+        for raw in [train(), test.clone()] {
+            let mut scaled = raw.clone();
+            scaler.transform(&mut scaled);
+            scaler.inverse_transform(&mut scaled);
+            assert_close(&scaled, &raw);
+        }
+    }
+
+    // target scaling: a prediction of the scaled labels is an unscaled one after
+    // inverse_transform
+    let labels = vec![vec![100.], vec![300.], vec![200.]];
+    let scaler = Scaler::fit(ScalingMethod::MinMax, &labels);
+    let mut predictions = vec![vec![0.], vec![1.], vec![0.25]];
+    scaler.inverse_transform(&mut predictions);
+    assert_close(&predictions, &[vec![100.], vec![300.], vec![150.]]);
+}
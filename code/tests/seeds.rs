@@ -0,0 +1,80 @@
+use cgp::datasets::split::{self, SplitConfig, SplitStrategy};
+use cgp::datasets::sr_benchmarks::{self, BenchmarkSettings, Sampling};
+use cgp::global_params::CgpParameters;
+use cgp::utils::random::{self, CgpRng};
+use cgp::utils::runner::Runner;
+use rand::Rng;
+
+// one-hot labels of two classes, so that the stratification is exercised
+fn class_labels() -> Vec<Vec<f32>> {
+    (0..60).map(|i| if i % 3 == 0 { vec![1., 0.] } else { vec![0., 1.] }).collect()
+}
+
+fn split_indices(strategy: SplitStrategy, seed: u64) -> Vec<[Vec<usize>; 3]> {
+    let config = SplitConfig { strategy, validation_ratio: 0.2, stratify: true, seed };
+    split::make_splits(&config, &class_labels())
+        .into_iter()
+        .map(|x| [x.train, x.validation, x.test])
+        .collect()
+}
+
+#[test]
+fn splits_follow_the_seed() {
+    for strategy in [
+        SplitStrategy::Holdout { test_ratio: 0.3, repetitions: 3 },
+        SplitStrategy::KFold { folds: 5, repeats: 2 },
+    ] {
+        let splits = split_indices(strategy.clone(), 11);
+        assert_eq!(splits, split_indices(strategy.clone(), 11), "{:?}", strategy);
+        assert_ne!(splits, split_indices(strategy.clone(), 12), "{:?}", strategy);
+    }
+}
+
+#[test]
+fn benchmark_data_follows_the_seed() {
+    let settings = |seed| BenchmarkSettings { sample_count: Some(20), seed };
+    // grids do not draw anything
+    let draws = |x: &[Sampling]| x.iter().any(|x| matches!(x, Sampling::Uniform(_, _, _)));
+    for benchmark in sr_benchmarks::benchmarks() {
+        if !draws(&benchmark.train) && !benchmark.test.as_deref().is_some_and(draws) {
+            continue;
+        }
+        let data = benchmark.generate(&settings(11));
+        assert_eq!(data, benchmark.generate(&settings(11)), "{}", benchmark.name);
+        assert_ne!(data, benchmark.generate(&settings(12)), "{}", benchmark.name);
+    }
+}
+
+fn fitness_sequence(rng: CgpRng) -> Vec<u32> {
+    let mut data_rng = random::benchmark_rng(0);
+    let data: Vec<Vec<f32>> = (0..50).map(|_| vec![data_rng.gen_range(-1.0..1.0)]).collect();
+    let labels = data.iter().map(|x| vec![x[0] * x[0] * x[0] + x[0]]).collect();
+    let params = CgpParameters { nbr_inputs: 1, graph_width: 20, nbr_outputs: 1, ..Default::default() };
+    let mut runner = Runner::new(params, data, labels, 0, 0.05, false, 0, rng);
+    runner.new_parents_by_method(false);
+    (0..100).map(|_| runner.learn_step(false).to_bits()).collect()
+}
+
+#[test]
+fn runs_follow_the_seed_and_repetition() {
+    let run = fitness_sequence(random::repetition_rng(11, 0));
+    assert_eq!(run, fitness_sequence(random::repetition_rng(11, 0)));
+    assert_ne!(run, fitness_sequence(random::repetition_rng(12, 0)));
+    assert_ne!(run, fitness_sequence(random::repetition_rng(11, 1)));
+}
+
+#[test]
+fn streams_of_one_seed_are_independent() {
+    let draws = |mut rng: CgpRng| (0..8).map(|_| rng.gen::<u64>()).collect::<Vec<_>>();
+    let streams = [
+        draws(random::benchmark_rng(11)),
+        draws(random::split_rng(11)),
+        draws(random::repetition_rng(11, 0)),
+        draws(random::repetition_rng(11, 1)),
+    ];
+    for i in 0..streams.len() {
+        for j in i + 1..streams.len() {
+            assert_ne!(streams[i], streams[j]);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use cgp::datasets::dataset::TaskKind;
+use cgp::datasets::split::{self, Split, SplitConfig, SplitStrategy};
+
+// 20 samples of class 0 and 40 of class 1, interleaved
+fn class_labels() -> Vec<Vec<f32>> {
+    (0..60).map(|i| if i % 3 == 0 { vec![1., 0.] } else { vec![0., 1.] }).collect()
+}
+
+fn splits(strategy: SplitStrategy, validation_ratio: f32, stratify: bool, labels: &[Vec<f32>]) -> Vec<Split> {
+    split::make_splits(&SplitConfig { strategy, validation_ratio, stratify, seed: 3 }, labels)
+}
+
+fn count_class_0(indices: &[usize], labels: &[Vec<f32>]) -> usize {
+    indices.iter().filter(|&&i| labels[i][0] == 1.).count()
+}
+
+fn sorted(parts: &[&Vec<usize>]) -> Vec<usize> {
+    let mut all: Vec<usize> = parts.iter().flat_map(|x| x.iter().cloned()).collect();
+    all.sort();
+    all
+}
+
+#[test]
+fn stratified_holdout_keeps_the_class_proportions() {
+    let labels = class_labels();
+    let holdout = SplitStrategy::Holdout { test_ratio: 0.25, repetitions: 4 };
+    for split in splits(holdout, 0.2, true, &labels) {
+        // 5 of 20 and 10 of 40 held out, then 3 of 15 and 6 of 30 for validation
+        assert_eq!((split.test.len(), count_class_0(&split.test, &labels)), (15, 5));
+        assert_eq!((split.validation.len(), count_class_0(&split.validation, &labels)), (9, 3));
+        assert_eq!((split.train.len(), count_class_0(&split.train, &labels)), (36, 12));
+        assert_eq!(sorted(&[&split.train, &split.validation, &split.test]), (0..60).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn k_folds_are_disjoint_cover_every_row_and_are_stratified() {
+    let labels = class_labels();
+    let all: Vec<usize> = (0..60).collect();
+    let splits = splits(SplitStrategy::KFold { folds: 5, repeats: 2 }, 0., true, &labels);
+    assert_eq!(splits.len(), 10);
+    for repeat in splits.chunks(5) {
+        let folds: Vec<&Vec<usize>> = repeat.iter().map(|x| &x.test).collect();
+        // disjoint and complete together
+        assert_eq!(sorted(&folds), all);
+        for split in repeat {
+            assert_eq!((split.test.len(), count_class_0(&split.test, &labels)), (12, 4));
+            assert_eq!(sorted(&[&split.train, &split.test]), all);
+            assert!(split.validation.is_empty());
+        }
+    }
+    // every repeat deals the rows out anew
+    assert_ne!(splits[0].test, splits[5].test);
+}
+
+#[test]
+fn regression_targets_are_never_stratified() {
+    assert!(!split::stratifies(TaskKind::Regression, true));
+    assert!(split::stratifies(TaskKind::Classification, true));
+    assert!(!split::stratifies(TaskKind::Classification, false));
+
+    // a single target column is one stratum even when stratification is asked for
+    let labels: Vec<Vec<f32>> = (0..30).map(|i| vec![i as f32]).collect();
+    let holdout = SplitStrategy::Holdout { test_ratio: 0.3, repetitions: 2 };
+    let stratified: Vec<Vec<usize>> = splits(holdout.clone(), 0., true, &labels).into_iter().map(|x| x.test).collect();
+    let plain: Vec<Vec<usize>> = splits(holdout, 0., false, &labels).into_iter().map(|x| x.test).collect();
+    assert_eq!(stratified, plain);
+}
@@ -0,0 +1,28 @@
+use cgp::datasets;
+use cgp::datasets::sr_benchmarks::{self, BenchmarkSettings, Sampling};
+use std::path::Path;
+
+#[test]
+fn grid_benchmarks_never_test_on_their_training_set() {
+    let settings = BenchmarkSettings::default();
+    for benchmark in sr_benchmarks::benchmarks() {
+        if !matches!(benchmark.train[0], Sampling::Grid(_, _, _)) {
+            continue;
+        }
+        let ((train, _), test) = benchmark.generate(&settings);
+        match (&benchmark.test, test) {
+            (Some(_), Some((test, _))) => assert_ne!(train, test, "{}", benchmark.name),
+            (None, None) => {}
+            (_, test) => panic!("{}: unexpected test set {:?}", benchmark.name, test.map(|x| x.0.len())),
+        }
+    }
+}
+
+#[test]
+fn grid_without_test_set_is_split_randomly() {
+    let settings = BenchmarkSettings::default();
+    let (_, dataset) = datasets::find("pagie-1", &settings).unwrap();
+    assert!(dataset.load_partitioned(Path::new("")).is_none());
+    // 26 x 26 grid points, each once
+    assert_eq!(dataset.load(Path::new("")).unwrap().0.len(), 26 * 26);
+}
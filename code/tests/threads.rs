@@ -0,0 +1,45 @@
+use cgp::global_params::CgpParameters;
+use cgp::utils::random;
+use cgp::utils::runner::Runner;
+use rand::Rng;
+
+// above twice the row chunk size of Node::execute, so that the nodes are
+// evaluated chunk by chunk on the pool
+const NBR_ROWS: usize = 20_000;
+
+// the fitness of every chromosome after every generation, as bits
+fn fitness_trajectory(nbr_threads: usize) -> Vec<Vec<u32>> {
+    let mut rng = random::benchmark_rng(3);
+    let data: Vec<Vec<f32>> = (0..NBR_ROWS).map(|_| vec![rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)]).collect();
+    let labels = data.iter().map(|x| vec![x[0] * x[0] - 0.7 * x[1]]).collect();
+    let params = CgpParameters {
+        nbr_inputs: 4,
+        nbr_constants: 2,
+        graph_width: 20,
+        nbr_outputs: 1,
+        linear_scaling: true,
+        constant_tuning_interval: 4,
+        constant_tuning_iterations: 3,
+        ..Default::default()
+    };
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(nbr_threads).build().unwrap();
+    pool.install(|| {
+        let mut runner = Runner::new(params, data, labels, 0, 0.05, false, 0, random::repetition_rng(3, 0));
+        runner.new_parents_by_method(false);
+        (0..12)
+            .map(|_| {
+                runner.learn_step(false);
+                runner.get_fitnesses().iter().map(|x| x.to_bits()).collect()
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn fitness_does_not_depend_on_the_number_of_threads() {
+    let single = fitness_trajectory(1);
+    assert_eq!(single, fitness_trajectory(4));
+    // the run has to actually go somewhere for the comparison to mean anything
+    assert_ne!(single.first(), single.last());
+}